@@ -42,7 +42,14 @@ mod imp {
         pub yubikey_enrollment: TemplateChild<Label>,
         #[template_child]
         pub device_id: TemplateChild<Label>,
+        #[template_child]
+        pub client_cert_status: TemplateChild<Label>,
+        #[template_child]
+        pub ca_cert_status: TemplateChild<Label>,
+        #[template_child]
+        pub cert_warning: TemplateChild<Label>,
         pub(super) refresh_cancel: RefCell<Option<CancelGuard>>,
+        pub(super) cert_handler: RefCell<Option<glib::SignalHandlerId>>,
     }
 
     #[glib::object_subclass]
@@ -115,6 +122,18 @@ impl AboutPage {
         self.imp().device_id.set_label("loading...");
 
         if let Some(app) = self.get_app_ref() {
+            self.refresh_certificates(&app);
+            if self.imp().cert_handler.borrow().is_none() {
+                let handler = app.cert_monitor().connect_changed(glib::clone!(
+                    #[weak(rename_to = page)]
+                    self,
+                    #[weak]
+                    app,
+                    move |_| page.refresh_certificates(&app)
+                ));
+                self.imp().cert_handler.replace(Some(handler));
+            }
+
             let cancellable = gio::Cancellable::new();
             self.imp()
                 .refresh_cancel
@@ -153,6 +172,30 @@ impl AboutPage {
         }
     }
 
+    fn refresh_certificates(&self, app: &ControlPanelGuiApplication) {
+        let imp = self.imp();
+        let Some(status) = app.certificate_status() else {
+            imp.client_cert_status.set_label("TLS disabled");
+            imp.ca_cert_status.set_label("TLS disabled");
+            imp.cert_warning.set_visible(false);
+            return;
+        };
+        for (label, info) in [
+            (&imp.client_cert_status, &status.cert),
+            (&imp.ca_cert_status, &status.ca),
+        ] {
+            match info {
+                Ok(info) => label.set_label(&info.summary()),
+                Err(e) => label.set_label(e),
+            }
+        }
+
+        let warning = app.certificate_warning();
+        imp.cert_warning
+            .set_label(warning.as_deref().unwrap_or_default());
+        imp.cert_warning.set_visible(warning.is_some());
+    }
+
     fn get_app_ref(&self) -> Option<ControlPanelGuiApplication> {
         gio::Application::default()
             .and_downcast::<ControlPanelGuiApplication>()
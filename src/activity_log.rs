@@ -0,0 +1,270 @@
+use chrono::{DateTime, Local, SecondsFormat};
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::control_action::ControlAction;
+use crate::prelude::*;
+use crate::service_model::ServiceModel;
+use crate::xdg;
+
+const LOG_FILE: &str = "activity.jsonl";
+/// The log is rotated once it reaches this size
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Rotated files kept next to the current one, `activity.jsonl.1` being the newest
+const MAX_ROTATED_FILES: usize = 3;
+/// Entries kept in memory and shown on the activity page
+pub const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Activity {
+    /// Control action issued from the panel
+    Action {
+        action: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Event received from the admin service, with the unit status afterwards
+    Event { event: String, status: String },
+}
+
+/// One line of the activity log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, glib::Boxed)]
+#[boxed_type(name = "ActivityEntry")]
+pub struct ActivityEntry {
+    /// RFC 3339 local time
+    pub time: String,
+    pub unit: String,
+    #[serde(flatten)]
+    pub activity: Activity,
+}
+
+impl ActivityEntry {
+    fn now(unit: String, activity: Activity) -> Self {
+        Self {
+            time: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            unit,
+            activity,
+        }
+    }
+
+    pub fn display_time(&self) -> String {
+        DateTime::parse_from_rfc3339(&self.time).map_or_else(
+            |_| self.time.clone(),
+            |time| time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+    }
+
+    pub fn description(&self) -> String {
+        match &self.activity {
+            Activity::Action {
+                action,
+                error: None,
+            } => format!("{action} requested"),
+            Activity::Action {
+                action,
+                error: Some(error),
+            } => format!("{action} failed: {error}"),
+            Activity::Event { event, status } => format!("{event}, now {status}"),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.activity, Activity::Action { error: Some(_), .. })
+    }
+}
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::glib;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::sync::OnceLock;
+
+    use super::ActivityEntry;
+
+    #[derive(Debug, Default)]
+    pub struct ActivityLog {
+        pub(super) entries: RefCell<VecDeque<ActivityEntry>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ActivityLog {
+        const NAME: &'static str = "ActivityLog";
+        type Type = super::ActivityLog;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for ActivityLog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("added")
+                        .param_types([ActivityEntry::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ActivityLog(ObjectSubclass<imp::ActivityLog>);
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl ActivityLog {
+    fn path() -> PathBuf {
+        xdg::state_dir().join(LOG_FILE)
+    }
+
+    fn rotated_path(index: usize) -> PathBuf {
+        xdg::state_dir().join(format!("{LOG_FILE}.{index}"))
+    }
+
+    /// Current file followed by the rotated ones, newest first
+    fn files() -> impl Iterator<Item = PathBuf> {
+        std::iter::once(Self::path()).chain((1..=MAX_ROTATED_FILES).map(Self::rotated_path))
+    }
+
+    /// Reads the latest `MAX_ENTRIES` entries of the persisted log
+    pub fn load(&self) {
+        let mut entries = VecDeque::new();
+        for path in Self::files() {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    warn!(
+                        "Failed to read activity log {path}: {e}",
+                        path = path.display()
+                    );
+                    continue;
+                }
+            };
+            // Unreadable lines, e.g. one cut short by a crash, are skipped
+            let older: Vec<ActivityEntry> = content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+            for entry in older.into_iter().rev() {
+                entries.push_front(entry);
+            }
+            if entries.len() >= MAX_ENTRIES {
+                break;
+            }
+        }
+        while entries.len() > MAX_ENTRIES {
+            entries.pop_front();
+        }
+        self.imp().entries.replace(entries);
+    }
+
+    /// Records control actions and watch events of `model`
+    pub fn watch(&self, model: &ServiceModel) {
+        model.connect_control_action_done(glib::clone!(
+            #[weak(rename_to = log)]
+            self,
+            move |_, obj, action: ControlAction, error| {
+                let action = action.label().to_owned();
+                log.record(obj.name(), Activity::Action { action, error });
+            }
+        ));
+        model.connect_watch_event(glib::clone!(
+            #[weak(rename_to = log)]
+            self,
+            move |_, obj, event| {
+                let activity = Activity::Event {
+                    event: event.to_owned(),
+                    status: obj.status_name().to_owned(),
+                };
+                log.record(obj.name(), activity);
+            }
+        ));
+    }
+
+    pub fn record(&self, unit: String, activity: Activity) {
+        let entry = ActivityEntry::now(unit, activity);
+        if let Err(e) = Self::append(&entry) {
+            warn!("Failed to write activity log: {e}");
+        }
+        {
+            let mut entries = self.imp().entries.borrow_mut();
+            entries.push_back(entry.clone());
+            if entries.len() > MAX_ENTRIES {
+                entries.pop_front();
+            }
+        }
+        self.emit_by_name::<()>("added", &[&entry]);
+    }
+
+    fn append(entry: &ActivityEntry) -> Result<(), anyhow::Error> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= MAX_FILE_SIZE) {
+            Self::rotate()?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Shifts `activity.jsonl` to `activity.jsonl.1` and so on, dropping the oldest file
+    fn rotate() -> std::io::Result<()> {
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = Self::rotated_path(index);
+            if from.exists() {
+                fs::rename(from, Self::rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(Self::path(), Self::rotated_path(1))
+    }
+
+    /// Entries in memory, oldest first
+    pub fn entries(&self) -> Vec<ActivityEntry> {
+        self.imp().entries.borrow().iter().cloned().collect()
+    }
+
+    /// Writes the whole persisted log, oldest entry first, e.g. to attach it to a bug report
+    pub fn export(&self, dest: &Path) -> Result<(), anyhow::Error> {
+        let files: Vec<PathBuf> = Self::files().collect();
+        let mut content = String::new();
+        for path in files.iter().rev() {
+            match fs::read_to_string(path) {
+                Ok(part) => content.push_str(&part),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        fs::write(dest, content)?;
+        Ok(())
+    }
+
+    pub fn connect_added<F: Fn(&Self, &ActivityEntry) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("added", false, move |values| {
+            let log = values[0].get::<Self>().unwrap();
+            let entry = values[1].get::<ActivityEntry>().unwrap();
+            f(&log, &entry);
+            None
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::activity_log::{ActivityEntry, ActivityLog, MAX_ENTRIES};
+use crate::error_popup::ErrorPopup;
+use crate::prelude::*;
+
+mod imp {
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{CompositeTemplate, Label, ListBox, SearchEntry, glib};
+    use std::cell::RefCell;
+
+    use crate::activity_log::ActivityLog;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/activity_page.ui")]
+    pub struct ActivityPage {
+        #[template_child]
+        pub unit_filter_entry: TemplateChild<SearchEntry>,
+        #[template_child]
+        pub empty_label: TemplateChild<Label>,
+        #[template_child]
+        pub entries_list_box: TemplateChild<ListBox>,
+
+        pub(super) log: RefCell<Option<ActivityLog>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ActivityPage {
+        const NAME: &'static str = "ActivityPage";
+        type Type = super::ActivityPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl ActivityPage {
+        #[template_callback]
+        fn on_filter_changed(&self) {
+            self.obj().refresh();
+        }
+
+        #[template_callback]
+        fn on_export_clicked(&self) {
+            self.obj().export();
+        }
+    }
+
+    impl ObjectImpl for ActivityPage {}
+    impl WidgetImpl for ActivityPage {}
+    impl BoxImpl for ActivityPage {}
+}
+
+glib::wrapper! {
+pub struct ActivityPage(ObjectSubclass<imp::ActivityPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for ActivityPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivityPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_log(&self, log: &ActivityLog) {
+        log.connect_added(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, entry| page.prepend(entry)
+        ));
+        self.imp().log.replace(Some(log.clone()));
+        self.refresh();
+    }
+
+    fn matches_filter(&self, entry: &ActivityEntry) -> bool {
+        let filter = self.imp().unit_filter_entry.text().to_lowercase();
+        filter.is_empty() || entry.unit.to_lowercase().contains(&filter)
+    }
+
+    /// Lists the entries matching the unit filter, newest first
+    fn refresh(&self) {
+        let list_box = self.imp().entries_list_box.get();
+        list_box.remove_all();
+        let entries = self
+            .imp()
+            .log
+            .borrow()
+            .as_ref()
+            .map(ActivityLog::entries)
+            .unwrap_or_default();
+        for entry in entries
+            .iter()
+            .rev()
+            .filter(|entry| self.matches_filter(entry))
+        {
+            list_box.append(&entry_row(entry));
+        }
+        self.imp()
+            .empty_label
+            .set_visible(list_box.row_at_index(0).is_none());
+    }
+
+    fn prepend(&self, entry: &ActivityEntry) {
+        if !self.matches_filter(entry) {
+            return;
+        }
+        let list_box = self.imp().entries_list_box.get();
+        list_box.prepend(&entry_row(entry));
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        if let Some(oldest) = list_box.row_at_index(MAX_ENTRIES as i32) {
+            list_box.remove(&oldest);
+        }
+        self.imp().empty_label.set_visible(false);
+    }
+
+    fn export(&self) {
+        let Some(log) = self.imp().log.borrow().clone() else {
+            return;
+        };
+        let dialog = gtk::FileDialog::builder()
+            .title("Export activity log")
+            .modal(true)
+            .initial_name("ctrl-panel-activity.jsonl")
+            .build();
+        let window = self.root().and_downcast::<gtk::Window>();
+
+        glib::spawn_future_local(async move {
+            let Ok(file) = dialog.save_future(window.as_ref()).await else {
+                return;
+            };
+            let Some(path) = file.path() else {
+                return;
+            };
+            if let Err(e) = log.export(&path) {
+                warn!(
+                    "Failed to export activity log to {path}: {e}",
+                    path = path.display()
+                );
+                let popup = ErrorPopup::new(&format!("Failed to export the activity log: {e}"));
+                popup.set_transient_for(window.as_ref());
+                popup.set_modal(true);
+                popup.present();
+            }
+        });
+    }
+}
+
+fn entry_row(entry: &ActivityEntry) -> gtk::Box {
+    let row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(20)
+        .margin_top(5)
+        .margin_bottom(5)
+        .build();
+    row.append(
+        &gtk::Label::builder()
+            .label(entry.display_time())
+            .css_classes(["dim-label", "numeric"])
+            .build(),
+    );
+    row.append(
+        &gtk::Label::builder()
+            .label(entry.unit.as_str())
+            .width_chars(32)
+            .xalign(0.)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build(),
+    );
+    let description = gtk::Label::builder()
+        .label(entry.description())
+        .xalign(0.)
+        .hexpand(true)
+        .wrap(true)
+        .build();
+    if entry.is_error() {
+        description.add_css_class("error");
+    }
+    row.append(&description);
+    row
+}
@@ -0,0 +1,155 @@
+use gtk::glib;
+use gtk::subclass::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+
+/// Application that can be started in an app VM
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CatalogEntry {
+    /// Application name as known to the admin service
+    pub name: String,
+    pub vm: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Bundled icon suffix (`chrome` for `icon_app_chrome.svg`) or an icon theme name as used
+    /// by desktop entries
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl CatalogEntry {
+    pub fn new(name: String, vm: String) -> Self {
+        Self {
+            name,
+            vm,
+            display_name: None,
+            icon: None,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Catalog file layout:
+///
+/// ```toml
+/// [[app]]
+/// name = "google-chrome"
+/// vm = "chrome-vm"
+/// display-name = "Google Chrome"
+/// icon = "chrome"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct CatalogFile {
+    #[serde(default, rename = "app")]
+    apps: Vec<CatalogEntry>,
+}
+
+mod imp {
+    use gtk::glib;
+    use gtk::subclass::prelude::*;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    use super::CatalogEntry;
+    use crate::file_watcher::FileWatcher;
+
+    #[derive(Debug, Default)]
+    pub struct AppCatalog {
+        pub(super) path: RefCell<PathBuf>,
+        pub(super) apps: RefCell<Vec<CatalogEntry>>,
+        pub(super) watcher: FileWatcher,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AppCatalog {
+        const NAME: &'static str = "AppCatalog";
+        type Type = super::AppCatalog;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for AppCatalog {}
+}
+
+glib::wrapper! {
+    /// Catalog of launchable applications
+    pub struct AppCatalog(ObjectSubclass<imp::AppCatalog>);
+}
+
+impl Default for AppCatalog {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl AppCatalog {
+    /// Loads the catalog file and reloads it whenever it changes on disk
+    pub fn watch(&self, path: &Path) {
+        *self.imp().path.borrow_mut() = path.to_owned();
+        self.imp().watcher.watch(
+            &[path],
+            glib::clone!(
+                #[weak(rename_to = catalog)]
+                self,
+                #[upgrade_or_default]
+                move || catalog.reload()
+            ),
+        );
+        self.imp().watcher.reload();
+    }
+
+    /// True when the catalog differs from the one read before
+    fn reload(&self) -> bool {
+        let path: PathBuf = self.imp().path.borrow().clone();
+        let apps = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<CatalogFile>(&content)
+                .map(|file| file.apps)
+                .unwrap_or_else(|e| {
+                    error!(
+                        "Failed to parse app catalog {path}: {e}",
+                        path = path.display()
+                    );
+                    Vec::new()
+                }),
+            Err(e) => {
+                debug!("No app catalog at {path}: {e}", path = path.display());
+                Vec::new()
+            }
+        };
+        if *self.imp().apps.borrow() == apps {
+            return false;
+        }
+        *self.imp().apps.borrow_mut() = apps;
+        true
+    }
+
+    /// Catalog apps completed with `apps` the catalog doesn't know about yet
+    pub fn merged(&self, apps: impl IntoIterator<Item = CatalogEntry>) -> Vec<CatalogEntry> {
+        let mut merged = self.imp().apps.borrow().clone();
+        for app in apps {
+            if !merged
+                .iter()
+                .any(|known| known.name == app.name && known.vm == app.vm)
+            {
+                merged.push(app);
+            }
+        }
+        merged
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.imp().watcher.connect_changed(glib::clone!(
+            #[weak(rename_to = catalog)]
+            self,
+            move |_| f(&catalog)
+        ))
+    }
+}
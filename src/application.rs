@@ -1,36 +1,82 @@
+use adw::prelude::*;
 use adw::subclass::prelude::*;
+use futures::future::LocalBoxFuture;
 use gio::ListModel;
-use gtk::prelude::*;
 use gtk::{gio, glib};
 
+use crate::activity_log::ActivityLog;
+use crate::app_catalog::{AppCatalog, CatalogEntry};
+use crate::audio_backend::{AudioBackend, AudioError, FakeAudioBackend, PulseAudioBackend};
+use crate::cert_monitor::{CertMonitor, CertStatus};
+use crate::config::{ClockFormat, Config, NightLightConfig, Profile};
 use crate::control_action::ControlAction;
 use crate::data_gobject::DataGObject;
+use crate::dbus_service::{self, DbusService, Request};
+use crate::deep_link::DeepLink;
+use crate::display_backend::{DisplayBackend, FakeDisplayBackend, SystemDisplayBackend};
 use crate::error_popup::ErrorPopup;
+use crate::launch_dialog::LaunchDialog;
+use crate::launch_history::LaunchHistory;
+use crate::locale_provider::{LocaleProvider, RegionChange};
+use crate::network_backend::{
+    FakeNetworkBackend, NetworkBackend, NetworkError, NetworkManagerBackend,
+};
+use crate::notifications::LifecycleEvent;
 use crate::plot::Plot;
+use crate::power_action::PowerAction;
+use crate::prelude::TypedListWrapperExt;
 use crate::security_icon::SecurityIcon;
 use crate::serie::Serie;
 use crate::service_gobject::ServiceGObject;
 pub use crate::service_model::StatsResponse;
 pub use crate::service_model::HostSysinfoStatus;
+use crate::session;
 use crate::settings_action::SettingsAction;
 use crate::status_icon::StatusIcon;
-use givc_client::endpoint::TlsConfig;
-use log::debug;
+use crate::update_backend::{AdminUpdateBackend, DirectoryUpdateBackend};
+use crate::updater::Updater;
+use crate::vm_capabilities::{Capability, VmCapabilities};
+use log::{debug, info, warn};
+use std::rc::Rc;
+
+/// Also the name of the application icon, bundled under the matching resource path
+pub const APP_ID: &str = "ae.tii.ghaf.controlpanelgui";
+
+const CERTIFICATE_NOTIFICATION_ID: &str = "certificate-expiry";
+const CERTIFICATE_CHECK_INTERVAL_SECS: u32 = 60 * 60;
+/// NetworkManager answers a scan request at once and finds the networks a few seconds later
+const WIFI_SCAN_WAIT_SECS: u32 = 4;
 
 mod imp {
     use adw::{prelude::*, subclass::prelude::*};
-    use gio::ListStore;
     use glib::Properties;
     use gtk::CssProvider;
     use gtk::{gdk, gio, glib};
     use std::cell::RefCell;
+    use std::rc::Rc;
 
+    use crate::activity_log::ActivityLog;
+    use crate::app_catalog::AppCatalog;
+    use crate::audio_backend::AudioBackend;
+    use crate::cert_monitor::CertMonitor;
+    use crate::config::{AdminConfig, Config, Profile, TlsSettings};
     use crate::connection_config::ConnectionConfig;
-    use crate::data_gobject::DataGObject;
-    use crate::language_region_notify_popup::LanguageRegionNotifyPopup;
-    use crate::locale_provider::{LanguageRegionData, LocaleProvider};
+    use crate::control_action::ControlAction;
+    use crate::deep_link::DeepLink;
+    use crate::display_backend::DisplayBackend;
+    use crate::endpoint::Endpoint;
+    use crate::language_region_notify_popup::{LanguageRegionNotifyPopup, SettingResult};
+    use crate::locale_provider::{
+        KeyboardLayout, LanguageRegionData, LocaleProvider, RegionChange,
+    };
+    use crate::network_backend::NetworkBackend;
+    use crate::power_action::PowerAction;
     use crate::prelude::*;
     use crate::service_model::ServiceModel;
+    use crate::session;
+    use crate::settings_action::SettingsAction;
+    use crate::updater::Updater;
+    use crate::vm_capabilities::VmCapabilities;
 
     use crate::ControlPanelGuiWindow;
 
@@ -38,6 +84,21 @@ mod imp {
     #[properties(wrapper_type = super::ControlPanelGuiApplication)]
     pub struct ControlPanelGuiApplication {
         pub(super) service_model: ServiceModel,
+        pub(super) config: RefCell<Config>,
+        pub(super) profile: RefCell<String>,
+        pub(super) vm_capabilities: VmCapabilities,
+        pub(super) app_catalog: AppCatalog,
+        pub(super) cert_monitor: CertMonitor,
+        pub(super) activity_log: ActivityLog,
+        pub(super) updater: Updater,
+        pub(super) display_backend: RefCell<Option<Rc<dyn DisplayBackend>>>,
+        pub(super) audio_backend: RefCell<Option<Rc<dyn AudioBackend>>>,
+        pub(super) network_backend: RefCell<Option<Rc<dyn NetworkBackend>>>,
+        pub(super) cert_warning: RefCell<Option<String>>,
+        /// Locale and timezone last accepted by the admin service. Those of the GUI VM say
+        /// nothing about the other VMs, so they are not used to skip a setting.
+        pub(super) applied_locale: RefCell<Option<String>>,
+        pub(super) applied_timezone: RefCell<Option<String>>,
 
         #[property(get, set)]
         window: RefCell<Option<ControlPanelGuiWindow>>,
@@ -62,6 +123,36 @@ mod imp {
     }
 
     impl ApplicationImpl for ControlPanelGuiApplication {
+        fn startup(&self) {
+            self.parent_startup();
+            let application = self.obj();
+            application.start_dbus_service();
+            application.check_certificates();
+            application.start_night_light();
+            application.follow_audio_streams();
+            // Certificates expire without any file change, so look at them regularly too
+            glib::timeout_add_seconds_local(
+                super::CERTIFICATE_CHECK_INTERVAL_SECS,
+                glib::clone!(
+                    #[weak]
+                    application,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        application.check_certificates();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+        }
+
+        fn shutdown(&self) {
+            if let Some(backend) = self.obj().display_backend() {
+                backend.stop_night_light();
+            }
+            self.parent_shutdown();
+        }
+
         // We connect to the activate callback to create a window when the application
         // has been launched. Additionally, this callback notifies us when the user
         // tries to launch a "second instance" of the application. When they try
@@ -83,31 +174,56 @@ mod imp {
                         let LanguageRegionData {
                             languages,
                             current_language,
+                            current_keyboard,
+                            keyboard_layouts,
                             timezones,
                             current_timezone,
                         } = LocaleProvider::get_timezone_locale_info().await;
 
-                        let index = current_language
-                            .and_then(|cur| languages.iter().position(|lang| lang.code == cur));
-                        let model: ListStore =
-                            languages.into_iter().map(DataGObject::from).collect();
-                        window.set_locale_model(model, index);
-
-                        let index = current_timezone
-                            .and_then(|cur| timezones.iter().position(|tz| tz.code == cur));
-                        let model: ListStore =
-                            timezones.into_iter().map(DataGObject::from).collect();
-                        window.set_timezone_model(model, index);
+                        window.set_locales(languages, current_language.as_deref());
+                        window.set_timezones(timezones, current_timezone.as_deref());
+
+                        window.set_keyboard_layouts(keyboard_layouts, current_keyboard.as_ref());
+
+                        match LocaleProvider::get_clock_status().await {
+                            Ok(status) => window.show_region_change(&RegionChange::Clock(status)),
+                            Err(e) => warn!("Error reading the clock status: {e}"),
+                        }
                     }
                 ));
+                let weak_window = window.downgrade();
+                glib::spawn_future_local(async move {
+                    let watched = LocaleProvider::watch(move |change| {
+                        if let Some(window) = weak_window.upgrade() {
+                            window.show_region_change(&change);
+                        }
+                    });
+                    if let Err(e) = watched.await {
+                        warn!("Locale and timezone changes are not followed: {e}");
+                    }
+                });
 
                 self.obj().set_window(&window);
+                application.refresh_display();
                 window.upcast()
             };
 
             // Ask the window manager/compositor to present the window
             window.present();
         }
+
+        // Links given on the command line of this or a second instance
+        fn open(&self, files: &[gio::File], _hint: &str) {
+            let application = self.obj();
+            application.present_window();
+            for file in files {
+                let uri = file.uri();
+                match uri.parse::<DeepLink>() {
+                    Ok(link) => application.open_link(link),
+                    Err(e) => warn!("Cannot open {uri}: {e}"),
+                }
+            }
+        }
     }
 
     impl ControlPanelGuiApplication {
@@ -124,28 +240,108 @@ mod imp {
             );
         }
 
-        pub fn set_locale_timezone(&self, locale: String, timezone: String) {
-            let popup = LanguageRegionNotifyPopup::new();
-            popup.set_transient_for(self.obj().active_window().as_ref());
-            popup.set_modal(true);
+        pub fn set_locale_timezone(
+            &self,
+            locale: String,
+            timezone: String,
+            keyboard: KeyboardLayout,
+        ) {
             glib::spawn_future_local(glib::clone!(
                 #[strong(rename_to = app)]
                 self.obj(),
                 async move {
-                    let locset = app.imp().service_model.set_locale(locale);
-                    if let Err(err) = locset.await {
-                        warn!("Locale setting failed: {err}");
+                    // Settings already applied are not applied again
+                    let current = LocaleProvider::get_current_settings().await;
+                    let imp = app.imp();
+                    let service_model = &imp.service_model;
+                    let locale_result = if imp.applied_locale.borrow().as_ref() == Some(&locale) {
+                        SettingResult::Unchanged
+                    } else {
+                        let result = service_model.set_locale(locale.clone()).await;
+                        if result.is_ok() {
+                            imp.applied_locale.replace(Some(locale.clone()));
+                        }
+                        result.into()
+                    };
+                    let keyboard_result = if current.keyboard.as_ref() == Some(&keyboard) {
+                        SettingResult::Unchanged
+                    } else {
+                        // The admin service has no keyboard call, only the GUI VM is set
+                        LocaleProvider::set_keyboard(&keyboard).await.into()
+                    };
+                    let timezone_result =
+                        if imp.applied_timezone.borrow().as_ref() == Some(&timezone) {
+                            SettingResult::Unchanged
+                        } else {
+                            let result = service_model.set_timezone(timezone.clone()).await;
+                            if result.is_ok() {
+                                imp.applied_timezone.replace(Some(timezone));
+                            }
+                            result.into()
+                        };
+
+                    // The session only needs restarting if it runs with another language
+                    let restart_needed = locale_result == SettingResult::Applied
+                        && current.locale.as_ref() != Some(&locale);
+                    let results = [
+                        ("Language", locale_result),
+                        ("Keyboard layout (GUI VM only)", keyboard_result),
+                        ("Timezone", timezone_result),
+                    ];
+                    for (setting, result) in &results {
+                        if let SettingResult::Failed(err) = result {
+                            warn!("{setting} setting failed: {err}");
+                        }
                     }
-                    let tzset = app.imp().service_model.set_timezone(timezone);
-                    if let Err(err) = tzset.await {
-                        warn!("Timezone setting failed: {err}");
+                    if results
+                        .iter()
+                        .all(|(_, result)| *result == SettingResult::Unchanged)
+                    {
+                        return;
                     }
-
-                    popup.present();
+                    app.imp().show_region_results(&results, restart_needed);
                 }
             ));
         }
 
+        /// Only a new locale needs a new session to take effect, so only then is
+        /// `restart_needed`
+        fn show_region_results(&self, results: &[(&str, SettingResult)], restart_needed: bool) {
+            let popup = LanguageRegionNotifyPopup::new();
+            popup.set_transient_for(self.obj().active_window().as_ref());
+            popup.set_modal(true);
+            for (setting, result) in results {
+                popup.add_result(setting, result);
+            }
+            popup.set_restart_needed(restart_needed);
+            popup.connect_local(
+                "log-out",
+                false,
+                glib::clone!(
+                    #[weak(rename_to = app)]
+                    self.obj(),
+                    #[upgrade_or]
+                    None,
+                    move |_| {
+                        glib::spawn_future_local(glib::clone!(
+                            #[strong]
+                            app,
+                            async move {
+                                if let Err(e) = session::log_out().await {
+                                    warn!("Log out failed: {e}");
+                                    app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                                        message: format!("Failed to log out: {e}"),
+                                    });
+                                }
+                            }
+                        ));
+                        None
+                    }
+                ),
+            );
+            popup.present();
+        }
+
         fn build_action<F: Fn(&Self) + 'static>(
             name: &str,
             cb: F,
@@ -156,18 +352,58 @@ mod imp {
                 .build()
         }
 
+        /// Action taking a unit name, used by notification buttons
+        fn build_unit_action<F: Fn(&super::ControlPanelGuiApplication, &str) + 'static>(
+            name: &str,
+            cb: F,
+        ) -> gio::ActionEntry<super::ControlPanelGuiApplication> {
+            type App = super::ControlPanelGuiApplication;
+            gio::ActionEntry::builder(name)
+                .parameter_type(Some(glib::VariantTy::STRING))
+                .activate(move |app: &App, _, unit| {
+                    if let Some(unit) = unit.and_then(glib::Variant::str) {
+                        cb(app, unit);
+                    }
+                })
+                .build()
+        }
+
         fn setup_gactions(&self) {
             let show_config_action = Self::build_action("show-config", Self::show_config);
             let quit_action = Self::build_action("quit", Self::clean_n_quit);
             let about_action = Self::build_action("about", Self::show_about);
-            self.obj()
-                .add_action_entries([show_config_action, quit_action, about_action]);
+            let restart_unit_action = Self::build_unit_action("restart-unit", |app, unit| {
+                if let Some(obj) = app.find_unit(unit) {
+                    app.control_service(ControlAction::Restart, obj);
+                } else {
+                    warn!("Cannot restart unknown unit {unit}");
+                }
+            });
+            let show_unit_action = Self::build_unit_action("show-unit", |app, unit| {
+                app.open_link(DeepLink::Unit(unit.into()));
+            });
+            let power_actions = [
+                ("suspend", PowerAction::Suspend),
+                ("reboot", PowerAction::Reboot),
+                ("power-off", PowerAction::PowerOff),
+                ("log-out", PowerAction::LogOut),
+            ]
+            .map(|(name, action)| {
+                Self::build_action(name, move |app| app.obj().request_power_action(action))
+            });
+            self.obj().add_action_entries([
+                show_config_action,
+                quit_action,
+                about_action,
+                restart_unit_action,
+                show_unit_action,
+            ]);
+            self.obj().add_action_entries(power_actions);
         }
 
         fn show_config(&self) {
-            let addr = self.service_model.address();
-            let port = self.service_model.port().try_into().unwrap_or(0u16);
-            let config = ConnectionConfig::new(&addr, port);
+            let profile = self.obj().active_profile();
+            let config = ConnectionConfig::new(&profile.admin.primary(), &profile.tls);
             config.set_transient_for(self.obj().active_window().as_ref());
             config.set_modal(true);
 
@@ -179,11 +415,31 @@ mod imp {
                     self.obj(),
                     move |values| {
                         //the value[0] is self
-                        let addr = values[1].get::<String>().unwrap();
-                        let port = values[2].get::<u32>().unwrap();
-                        debug!("New config applied: address {addr}, port {port}");
-                        app.imp().service_model.set_address(addr);
-                        app.imp().service_model.set_port(port);
+                        let endpoint = values[1].get::<Endpoint>().unwrap();
+                        let tls = values[2].get::<TlsSettings>().unwrap();
+                        debug!(
+                            "New config applied to {name}: endpoint {endpoint}, TLS {enable}",
+                            name = profile.name,
+                            enable = tls.enable
+                        );
+                        let profile = Profile {
+                            admin: AdminConfig {
+                                endpoint: Some(endpoint),
+                                ..profile.admin.clone()
+                            },
+                            tls,
+                            ..profile.clone()
+                        };
+                        let saved = {
+                            let mut config = app.imp().config.borrow_mut();
+                            config.set_profile(profile.clone());
+                            config.save_profile(&profile)
+                        };
+                        if let Err(e) = saved {
+                            warn!("Failed to save connection configuration: {e}");
+                        }
+                        app.connect_profile(&profile);
+                        app.check_certificates();
                         None
                     }
                 ),
@@ -200,7 +456,7 @@ mod imp {
             let window = self.obj().active_window().unwrap();
             let about = adw::AboutDialog::builder()
                 .application_name("Ghaf Control Panel")
-                .application_icon("ae.tii.ghaf./controlpanelgui")
+                .application_icon(super::APP_ID)
                 .developer_name("dmitry")
                 .developers(vec!["dmitry"])
                 .copyright("© 2024 dmitry")
@@ -221,13 +477,7 @@ glib::wrapper! {
 }
 
 impl ControlPanelGuiApplication {
-    pub fn new(
-        application_id: &str,
-        flags: gio::ApplicationFlags,
-        addr: String,
-        port: u16,
-        tls_info: Option<(String, TlsConfig)>,
-    ) -> Self {
+    pub fn new(application_id: &str, flags: gio::ApplicationFlags, config: Config) -> Self {
         let _ = DataGObject::static_type();
         let _ = Plot::static_type();
         let _ = Serie::static_type();
@@ -239,19 +489,529 @@ impl ControlPanelGuiApplication {
             .property("flags", flags)
             .build();
 
-        app.imp().service_model.set_address(addr);
-        app.imp().service_model.set_port(u32::from(port));
-        if let Some((addr, tls_info)) = tls_info {
-            app.imp().service_model.set_tls_info(addr, tls_info);
+        app.connect_profile(&config.startup_profile());
+        app.imp().vm_capabilities.watch(&config.capabilities_path());
+        app.imp().app_catalog.watch(&config.files.app_catalog);
+        app.imp().cert_monitor.connect_changed(glib::clone!(
+            #[weak]
+            app,
+            move |_| app.reload_certificates()
+        ));
+        app.imp()
+            .service_model
+            .connect_lifecycle_event(glib::clone!(
+                #[weak]
+                app,
+                move |_, obj, event| app.notify_lifecycle_event(obj, event)
+            ));
+        app.imp().activity_log.load();
+        app.imp().activity_log.watch(&app.imp().service_model);
+        match &config.updates.manifest_dir {
+            Some(dir) => {
+                info!("Updates are read from {dir}", dir = dir.display());
+                app.imp()
+                    .updater
+                    .set_backend(DirectoryUpdateBackend::new(dir.clone()));
+            }
+            None => app.imp().updater.set_backend(AdminUpdateBackend),
         }
+        let display_backend: Rc<dyn DisplayBackend> = if config.display.fake {
+            info!("Display settings are simulated");
+            Rc::new(FakeDisplayBackend::new())
+        } else {
+            Rc::new(SystemDisplayBackend::new())
+        };
+        app.imp().display_backend.replace(Some(display_backend));
+        let audio_backend: Rc<dyn AudioBackend> = if config.audio.fake {
+            info!("Audio settings are simulated");
+            Rc::new(FakeAudioBackend::new())
+        } else {
+            Rc::new(PulseAudioBackend::new(config.audio.server.clone()))
+        };
+        app.imp().audio_backend.replace(Some(audio_backend));
+        let network_backend: Rc<dyn NetworkBackend> = if config.network.fake {
+            info!("Network settings are simulated");
+            Rc::new(FakeNetworkBackend::new())
+        } else {
+            Rc::new(NetworkManagerBackend::new(
+                config.network.bus_address.clone(),
+            ))
+        };
+        app.imp().network_backend.replace(Some(network_backend));
+        app.imp().config.replace(config);
 
         app
     }
 
+    fn start_dbus_service(&self) {
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                let (service, requests) = match DbusService::start().await {
+                    Ok(started) => started,
+                    Err(e) => {
+                        warn!("D-Bus interface is not available: {e}");
+                        return;
+                    }
+                };
+                info!("D-Bus interface exported as {}", dbus_service::BUS_NAME);
+                app.imp()
+                    .service_model
+                    .connect_unit_status_changed(move |_, obj| {
+                        let service = service.clone();
+                        let unit = obj.name();
+                        let status = obj.status_name();
+                        glib::spawn_future_local(async move {
+                            if let Err(e) = service.emit_unit_status_changed(&unit, status).await {
+                                warn!("Failed to signal status of {unit}: {e}");
+                            }
+                        });
+                    });
+                while let Ok((request, reply)) = requests.recv().await {
+                    let result = app.handle_dbus_request(request).await;
+                    let _ = reply.send(result).await;
+                }
+            }
+        ));
+    }
+
+    async fn handle_dbus_request(&self, request: Request) -> Result<(), String> {
+        debug!("D-Bus request: {request:?}");
+        match request {
+            Request::ShowPage(page) => {
+                self.present_window().show_page(page);
+                Ok(())
+            }
+            Request::SelectUnit(unit) => {
+                if self.present_window().select_unit(&unit) {
+                    Ok(())
+                } else {
+                    Err(format!("Unknown unit {unit}"))
+                }
+            }
+            Request::Control(action, unit) => {
+                let obj = self
+                    .find_unit(&unit)
+                    .ok_or_else(|| format!("Unknown unit {unit}"))?;
+                self.imp()
+                    .service_model
+                    .control_service(action, &obj)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Creates the window if needed and raises it
+    fn present_window(&self) -> crate::ControlPanelGuiWindow {
+        self.activate();
+        self.window().expect("activate creates the window")
+    }
+
+    pub fn open_link(&self, link: DeepLink) {
+        debug!("Opening {link}");
+        let window = self.present_window();
+        let unit = match link {
+            DeepLink::Page(page) => {
+                window.show_page(page);
+                return;
+            }
+            DeepLink::Unit(unit) => unit,
+        };
+        if window.select_unit(&unit) {
+            return;
+        }
+        if self.get_model().n_items() > 0 {
+            warn!("Unknown unit {unit}");
+            return;
+        }
+        // The unit list arrives after startup, select the unit once it is there
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                if app.imp().service_model.wait_connected().await.is_ok()
+                    && !window.select_unit(&unit)
+                {
+                    warn!("Unknown unit {unit}");
+                }
+            }
+        ));
+    }
+
+    /// Finds a unit by its name, or a VM by its VM name
+    pub fn find_unit(&self, unit: &str) -> Option<ServiceGObject> {
+        self.get_model()
+            .wrap::<ServiceGObject>()
+            .iter()
+            .find(|obj| obj.name() == unit || (obj.is_vm() && obj.vm_name() == unit))
+    }
+
     pub fn get_model(&self) -> ListModel {
         self.imp().service_model.clone().upcast()
     }
 
+    pub fn connect_unit_status_changed<F: Fn(&ServiceGObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.imp()
+            .service_model
+            .connect_unit_status_changed(move |_, obj| f(obj))
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.imp().config.borrow().profile_names()
+    }
+
+    pub fn active_profile(&self) -> Profile {
+        let config = self.imp().config.borrow();
+        config
+            .profile(&self.imp().profile.borrow())
+            .unwrap_or_else(|| config.startup_profile())
+    }
+
+    pub fn switch_profile(&self, name: &str) {
+        if *self.imp().profile.borrow() == name {
+            return;
+        }
+        let Some(profile) = self.imp().config.borrow().profile(name) else {
+            warn!("Unknown connection profile {name}");
+            return;
+        };
+        info!("Switching to connection profile {name}");
+        self.connect_profile(&profile);
+        self.check_certificates();
+    }
+
+    fn connect_profile(&self, profile: &Profile) {
+        let model = &self.imp().service_model;
+        model.set_endpoints(profile.admin.endpoints());
+        model.set_tls_info(profile.tls.tls_info());
+        self.imp().cert_monitor.watch(&profile.tls);
+        self.imp().profile.replace(profile.name.clone());
+    }
+
+    pub fn cert_monitor(&self) -> CertMonitor {
+        self.imp().cert_monitor.clone()
+    }
+
+    pub fn certificate_status(&self) -> Option<CertStatus> {
+        self.imp().cert_monitor.status()
+    }
+
+    pub fn certificate_warning(&self) -> Option<String> {
+        let warning_days = self.imp().config.borrow().certificates.expiry_warning_days;
+        self.certificate_status()
+            .and_then(|status| status.expiry_warning(warning_days))
+    }
+
+    /// Reconnects with the rotated certificates
+    fn reload_certificates(&self) {
+        info!("Certificates changed on disk, reconnecting");
+        let tls_info = self.active_profile().tls.tls_info();
+        self.imp().service_model.set_tls_info(tls_info);
+        self.check_certificates();
+    }
+
+    /// Notifies once per distinct expiry warning, withdrawing it when certificates are renewed
+    pub fn check_certificates(&self) {
+        let warning = self.certificate_warning();
+        if *self.imp().cert_warning.borrow() == warning {
+            return;
+        }
+        if let Some(warning) = &warning {
+            warn!("{warning}");
+            let notification = gio::Notification::new("Certificate expiry");
+            notification.set_body(Some(warning));
+            notification.set_priority(gio::NotificationPriority::High);
+            self.send_notification(Some(CERTIFICATE_NOTIFICATION_ID), &notification);
+        } else {
+            self.withdraw_notification(CERTIFICATE_NOTIFICATION_ID);
+        }
+        self.imp().cert_warning.replace(warning);
+    }
+
+    /// One notification per unit, a newer event replaces the previous one
+    fn notify_lifecycle_event(&self, obj: &ServiceGObject, event: LifecycleEvent) {
+        let config = self.imp().config.borrow();
+        let notifications = &config.notifications;
+        if !event.is_enabled(notifications) || notifications.muted_vms.contains(&obj.vm_name()) {
+            return;
+        }
+        let id = format!("lifecycle-{name}", name = obj.name());
+        self.send_notification(Some(&id), &event.notification(obj));
+    }
+
+    pub fn is_vm_muted(&self, vm: &str) -> bool {
+        self.imp()
+            .config
+            .borrow()
+            .notifications
+            .muted_vms
+            .contains(vm)
+    }
+
+    fn set_vm_muted(&self, vm: String, muted: bool) {
+        if self.is_vm_muted(&vm) == muted {
+            return;
+        }
+        let mut config = self.imp().config.borrow_mut();
+        let muted_vms = &mut config.notifications.muted_vms;
+        if muted {
+            muted_vms.insert(vm);
+        } else {
+            muted_vms.remove(&vm);
+        }
+        if let Err(e) = config.save_muted_vms() {
+            warn!("Failed to save notification settings: {e}");
+        }
+    }
+
+    pub fn clock_format(&self) -> ClockFormat {
+        self.imp().config.borrow().clock.format
+    }
+
+    fn set_clock_format(&self, format: ClockFormat) {
+        let mut config = self.imp().config.borrow_mut();
+        if config.clock.format == format {
+            return;
+        }
+        config.clock.format = format;
+        if let Err(e) = config.save_clock_format() {
+            warn!("Failed to save the clock format: {e}");
+        }
+    }
+
+    pub fn night_light(&self) -> NightLightConfig {
+        self.imp().config.borrow().display.night_light.clone()
+    }
+
+    fn display_backend(&self) -> Option<Rc<dyn DisplayBackend>> {
+        self.imp().display_backend.borrow().clone()
+    }
+
+    /// Follows the saved schedule from startup, the Display page need not be opened
+    fn start_night_light(&self) {
+        let night_light = self.night_light();
+        if !night_light.enabled {
+            return;
+        }
+        if let Some(Err(e)) = self
+            .display_backend()
+            .map(|backend| backend.set_night_light(&night_light))
+        {
+            warn!("Failed to start the night light: {e}");
+        }
+    }
+
+    /// Reads the outputs and the backlight level into the Display page
+    fn refresh_display(&self) {
+        let Some(backend) = self.display_backend() else {
+            return;
+        };
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                let outputs = backend.outputs().await.unwrap_or_else(|e| {
+                    warn!("Failed to read the display outputs: {e}");
+                    Vec::new()
+                });
+                let brightness = backend.brightness().await.unwrap_or_else(|e| {
+                    warn!("Failed to read the brightness: {e}");
+                    None
+                });
+                if let Some(window) = app.window() {
+                    window.set_outputs(outputs);
+                    window.set_brightness(brightness);
+                }
+            }
+        ));
+    }
+
+    fn audio_backend(&self) -> Option<Rc<dyn AudioBackend>> {
+        self.imp().audio_backend.borrow().clone()
+    }
+
+    /// Keeps muting the new streams of VMs muted on the Audio page
+    fn follow_audio_streams(&self) {
+        let Some(backend) = self.audio_backend() else {
+            return;
+        };
+        glib::spawn_future_local(async move {
+            if let Err(e) = backend.follow_streams().await {
+                warn!("New streams of muted VMs are not muted anymore: {e}");
+            }
+        });
+    }
+
+    /// Reads the devices and VM streams into the Audio page
+    fn refresh_audio(&self) {
+        let Some(backend) = self.audio_backend() else {
+            return;
+        };
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                let state = backend.state().await.map_err(|e| {
+                    warn!("Failed to read the audio devices: {e}");
+                    format!("The sound server cannot be reached: {e}")
+                });
+                if let Some(window) = app.window() {
+                    window.set_audio_state(state);
+                }
+            }
+        ));
+    }
+
+    /// Runs an audio change, the page is only refreshed after a failure or when `refresh`
+    /// since sliders send many changes while dragged
+    fn change_audio<F>(&self, description: &'static str, refresh: bool, change: F)
+    where
+        F: for<'a> FnOnce(&'a dyn AudioBackend) -> LocalBoxFuture<'a, Result<(), AudioError>>
+            + 'static,
+    {
+        let Some(backend) = self.audio_backend() else {
+            return;
+        };
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                let result = change(&*backend).await;
+                if let Err(e) = &result {
+                    warn!("Failed to {description}: {e}");
+                }
+                if refresh || result.is_err() {
+                    app.refresh_audio();
+                }
+            }
+        ));
+    }
+
+    fn network_backend(&self) -> Option<Rc<dyn NetworkBackend>> {
+        self.imp().network_backend.borrow().clone()
+    }
+
+    /// Reads the interfaces and Wi-Fi networks into the Network page
+    fn refresh_network(&self) {
+        let Some(backend) = self.network_backend() else {
+            return;
+        };
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                let state = backend.state().await.map_err(|e| {
+                    warn!("Failed to read the network interfaces: {e}");
+                    format!("NetworkManager of the net-vm cannot be reached: {e}")
+                });
+                if let Some(window) = app.window() {
+                    window.set_network_state(state);
+                }
+            }
+        ));
+    }
+
+    fn scan_wifi(&self, interface: String) {
+        let Some(backend) = self.network_backend() else {
+            return;
+        };
+        if let Some(window) = self.window() {
+            window.set_wifi_scanning(true);
+        }
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                match backend.scan(&interface).await {
+                    Ok(()) => glib::timeout_future_seconds(WIFI_SCAN_WAIT_SECS).await,
+                    // NetworkManager refuses too frequent scans, the last results stay shown
+                    Err(e) => warn!("Wi-Fi scan on {interface} failed: {e}"),
+                }
+                if let Some(window) = app.window() {
+                    window.set_wifi_scanning(false);
+                }
+                app.refresh_network();
+            }
+        ));
+    }
+
+    /// Runs a network change, failures are shown in a popup and the page is refreshed after
+    fn change_network<F>(&self, description: String, change: F)
+    where
+        F: for<'a> FnOnce(&'a dyn NetworkBackend) -> LocalBoxFuture<'a, Result<(), NetworkError>>
+            + 'static,
+    {
+        let Some(backend) = self.network_backend() else {
+            return;
+        };
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = app)]
+            self,
+            async move {
+                if let Err(e) = change(&*backend).await {
+                    warn!("Failed to {description}: {e}");
+                    app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                        message: format!("Failed to {description}: {e}"),
+                    });
+                }
+                app.refresh_network();
+            }
+        ));
+    }
+
+    fn set_night_light(&self, night_light: NightLightConfig) {
+        if let Some(Err(e)) = self
+            .display_backend()
+            .map(|backend| backend.set_night_light(&night_light))
+        {
+            warn!("Night light setting failed: {e}");
+            if let Some(window) = self.window() {
+                window.set_night_light(&self.night_light());
+            }
+            self.perform_setting_action(SettingsAction::ShowErrorPopup {
+                message: format!("Failed to change the night light: {e}"),
+            });
+            return;
+        }
+        let mut config = self.imp().config.borrow_mut();
+        config.display.night_light = night_light;
+        if let Err(e) = config.save_night_light() {
+            warn!("Failed to save the night light schedule: {e}");
+        }
+    }
+
+    pub fn activity_log(&self) -> ActivityLog {
+        self.imp().activity_log.clone()
+    }
+
+    pub fn updater(&self) -> Updater {
+        self.imp().updater.clone()
+    }
+
+    pub fn vm_capabilities(&self) -> VmCapabilities {
+        self.imp().vm_capabilities.clone()
+    }
+
+    pub fn app_catalog(&self) -> AppCatalog {
+        self.imp().app_catalog.clone()
+    }
+
+    /// Apps from the catalog file, completed with the app units currently running
+    pub fn available_apps(&self) -> Vec<CatalogEntry> {
+        self.imp().app_catalog.merged(
+            self.get_model()
+                .wrap::<ServiceGObject>()
+                .iter()
+                .filter(ServiceGObject::is_app)
+                .map(|obj| CatalogEntry::new(obj.display_name(), obj.vm_name())),
+        )
+    }
+
     pub fn get_stats(
         &self,
         vm: String,
@@ -271,72 +1031,372 @@ impl ControlPanelGuiApplication {
             #[strong(rename_to = app)]
             self,
             async move {
-                match action {
-                    ControlAction::Start => app
-                        .imp()
-                        .service_model
-                        .start_service(object)
-                        .await
-                        .map(|_| ()),
-                    ControlAction::Restart => app
-                        .imp()
-                        .service_model
-                        .restart_service(&object)
-                        .await
-                        .map(|_| ()),
-                    ControlAction::Pause => app.imp().service_model.pause_service(&object).await,
-                    ControlAction::Resume => app.imp().service_model.resume_service(&object).await,
-                    ControlAction::Shutdown => app.imp().service_model.stop_service(&object).await,
+                if let Err(err) = app
+                    .imp()
+                    .service_model
+                    .control_service(action, &object)
+                    .await
+                {
+                    warn!("{action:?} of {name} failed: {err}", name = object.name());
                 }
             }
         ));
     }
 
-    fn open_wireguard(&self, vm: &ServiceGObject) {
-        debug!("wireguard vm {vm}", vm = vm.name()); // Output: business-vm
+    pub fn control_services(&self, action: ControlAction, objects: Vec<ServiceGObject>) {
+        debug!(
+            "Control {count} services, {action:?}",
+            count = objects.len()
+        );
+        let (objects, skipped): (Vec<_>, Vec<_>) = objects
+            .into_iter()
+            .partition(ServiceGObject::is_controllable);
+        glib::spawn_future_local(glib::clone!(
+            #[strong(rename_to = app)]
+            self,
+            async move {
+                let results = app
+                    .imp()
+                    .service_model
+                    .control_services(action, objects)
+                    .await;
+                app.show_bulk_summary(action, &results, &skipped);
+            }
+        ));
+    }
 
-        if vm.is_vm() {
-            let vm_name = vm.vm_name();
-            debug!("wireguard app name {vm_name}"); // Output: business-vm
-            glib::spawn_future_local(glib::clone!(
+    fn show_bulk_summary(
+        &self,
+        action: ControlAction,
+        results: &[(ServiceGObject, Result<(), anyhow::Error>)],
+        skipped: &[ServiceGObject],
+    ) {
+        let succeeded = results.iter().filter(|(_, res)| res.is_ok()).count();
+        let failed = results.len() - succeeded;
+        let heading = format!(
+            "{action}: {succeeded} succeeded, {failed} failed",
+            action = action.label()
+        );
+        let body = results
+            .iter()
+            .map(|(obj, res)| match res {
+                Ok(()) => format!("✓ {name}", name = obj.name()),
+                Err(err) => format!("✗ {name}: {err}", name = obj.name()),
+            })
+            .chain(
+                skipped
+                    .iter()
+                    .map(|obj| format!("– {name}: cannot be controlled", name = obj.name())),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let dialog = adw::AlertDialog::new(Some(&heading), Some(&body));
+        dialog.add_response("close", "Close");
+        dialog.present(self.active_window().as_ref());
+    }
+
+    /// Confirms `action` with the running app VMs listed, optionally shutting them down one by
+    /// one before the action so they are not cut short
+    pub fn request_power_action(&self, action: PowerAction) {
+        let Some(window) = self.active_window() else {
+            return;
+        };
+        let running = self.imp().service_model.running_app_vms();
+        let body = if running.is_empty() {
+            "No app VM is running.".to_owned()
+        } else {
+            let names = running
+                .iter()
+                .map(|obj| format!("• {name}", name = obj.vm_name()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("These app VMs are running:\n{names}")
+        };
+        let dialog = adw::AlertDialog::new(
+            Some(&format!("Do you want to {verb}?", verb = action.verb())),
+            Some(&body),
+        );
+        let stop_vms = gtk::CheckButton::builder()
+            .label("Gracefully stop app VMs first")
+            .visible(!running.is_empty())
+            .build();
+        dialog.set_extra_child(Some(&stop_vms));
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("confirm", action.label());
+        dialog.set_response_appearance("confirm", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        glib::spawn_future_local(glib::clone!(
+            #[strong(rename_to = app)]
+            self,
+            async move {
+                if dialog.choose_future(&window).await != "confirm" {
+                    return;
+                }
+                let model = &app.imp().service_model;
+                if stop_vms.is_active() {
+                    info!(
+                        "Stopping {count} app VMs before {action:?}",
+                        count = running.len()
+                    );
+                    if let Err(e) = model.stop_vms_in_sequence(&running).await {
+                        warn!("{action:?} cancelled: {e}");
+                        app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                            message: format!("{e}, the device was left running"),
+                        });
+                        return;
+                    }
+                }
+                info!("Requesting {action:?}");
+                let result = match action {
+                    PowerAction::Suspend => model.suspend_system().await,
+                    PowerAction::Reboot => model.reboot_system().await,
+                    PowerAction::PowerOff => model.power_off_system().await,
+                    PowerAction::LogOut => session::log_out().await.map_err(Into::into),
+                };
+                if let Err(e) = result {
+                    warn!("{action:?} failed: {e}");
+                    app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                        message: format!("Failed to {verb}: {e}", verb = action.verb()),
+                    });
+                }
+            }
+        ));
+    }
+
+    fn launch_capability(&self, vm: &ServiceGObject, capability: Capability) {
+        if !vm.is_vm() {
+            return;
+        }
+        let vm_name = vm.vm_name();
+        debug!("Launching {app} in {vm_name}", app = capability.app);
+        glib::spawn_future_local(glib::clone!(
+            #[strong(rename_to = app)]
+            self,
+            async move {
+                if let Err(err) = app
+                    .imp()
+                    .service_model
+                    .start_app_in_vm(capability.app.clone(), vm_name.clone(), capability.args)
+                    .await
+                {
+                    warn!(
+                        "Launching {name} in {vm_name} failed: {err}",
+                        name = capability.app
+                    );
+                    app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                        message: format!(
+                            "Failed to start {label}: {err}",
+                            label = capability.label
+                        ),
+                    });
+                }
+            }
+        ));
+    }
+
+    fn show_launch_dialog(&self, object: &ServiceGObject) {
+        let app_name = object.display_name();
+        // The VM of the selected unit goes first, followed by other VMs running the same app
+        let mut vms = vec![object.vm_name()];
+        for vm in self
+            .get_model()
+            .wrap::<ServiceGObject>()
+            .iter()
+            .filter(|obj| obj.is_app() && obj.display_name() == app_name)
+            .map(|obj| obj.vm_name())
+        {
+            if !vms.contains(&vm) {
+                vms.push(vm);
+            }
+        }
+
+        let history = LaunchHistory::load();
+        let dialog = LaunchDialog::new(&app_name, &vms, history.entries(&app_name));
+        dialog.set_transient_for(self.active_window().as_ref());
+        dialog.set_modal(true);
+        dialog.connect_local(
+            "launch-requested",
+            false,
+            glib::clone!(
                 #[strong(rename_to = app)]
                 self,
-                async move {
-                    app.imp()
-                        .service_model
-                        .start_app_in_vm("wireguard-gui".into(), vm_name, vec![])
-                        .await
-                        .ok();
+                move |values| {
+                    //the value[0] is self
+                    let name = values[1].get::<String>().unwrap();
+                    let vm = values[2].get::<String>().unwrap();
+                    let args = values[3].get::<Vec<String>>().unwrap();
+                    app.launch_app(name, vm, args);
+                    None
                 }
-            ));
-        }
+            ),
+        );
+        dialog.present();
+    }
+
+    /// Only successful launches are kept in the launch history
+    pub fn launch_app(&self, name: String, vm: String, args: Vec<String>) {
+        glib::spawn_future_local(glib::clone!(
+            #[strong(rename_to = app)]
+            self,
+            async move {
+                match app
+                    .imp()
+                    .service_model
+                    .start_app_in_vm(name.clone(), vm.clone(), args.clone())
+                    .await
+                {
+                    Ok(_) => {
+                        let mut history = LaunchHistory::load();
+                        history.record(&name, args);
+                        if let Err(err) = history.save() {
+                            warn!("Failed to save launch history: {err}");
+                        }
+                    }
+                    Err(err) => {
+                        warn!("Launching {name} in {vm} failed: {err}");
+                        app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                            message: format!("Failed to launch {name} in {vm}: {err}"),
+                        });
+                    }
+                }
+            }
+        ));
     }
 
     pub fn perform_setting_action(&self, action: SettingsAction) {
-        debug!("Performing settings action... {action:?}");
+        match &action {
+            // Keeps the Wi-Fi password out of the log
+            SettingsAction::ConnectWifi {
+                interface, ssid, ..
+            } => debug!("Performing settings action... connect {interface} to {ssid}"),
+            _ => debug!("Performing settings action... {action:?}"),
+        }
         match action {
-            SettingsAction::RegionNLanguage { locale, timezone } => {
-                self.imp().set_locale_timezone(locale, timezone);
+            SettingsAction::RegionNLanguage {
+                locale,
+                timezone,
+                keyboard,
+            } => {
+                self.imp().set_locale_timezone(locale, timezone, keyboard);
             }
+            SettingsAction::SetClockFormat { format } => self.set_clock_format(format),
+            SettingsAction::ConfigureOutput {
+                output,
+                mode,
+                scale,
+            } => {
+                let Some(backend) = self.display_backend() else {
+                    return;
+                };
+                glib::spawn_future_local(glib::clone!(
+                    #[strong(rename_to = app)]
+                    self,
+                    async move {
+                        if let Err(e) = backend.configure_output(&output, &mode, scale).await {
+                            warn!("Display configuration failed: {e}");
+                            app.perform_setting_action(SettingsAction::ShowErrorPopup {
+                                message: format!("Failed to configure {output}: {e}"),
+                            });
+                        }
+                        // Also shows what the compositor accepted
+                        app.refresh_display();
+                    }
+                ));
+            }
+            SettingsAction::SetBrightness { brightness } => {
+                let Some(backend) = self.display_backend() else {
+                    return;
+                };
+                glib::spawn_future_local(glib::clone!(
+                    #[strong(rename_to = app)]
+                    self,
+                    async move {
+                        // Not a popup, the slider sends many changes while dragged
+                        if let Err(e) = backend.set_brightness(brightness).await {
+                            warn!("Brightness setting failed: {e}");
+                            app.refresh_display();
+                        }
+                    }
+                ));
+            }
+            SettingsAction::SetNightLight { night_light } => self.set_night_light(night_light),
+            SettingsAction::AudioRefreshRequest => self.refresh_audio(),
+            SettingsAction::SetDefaultAudioDevice { kind, name } => {
+                self.change_audio("change the default audio device", true, move |backend| {
+                    Box::pin(async move { backend.set_default_device(kind, &name).await })
+                });
+            }
+            SettingsAction::SetAudioDeviceVolume { kind, name, volume } => {
+                self.change_audio("set the audio volume", false, move |backend| {
+                    Box::pin(async move { backend.set_device_volume(kind, &name, volume).await })
+                });
+            }
+            SettingsAction::SetAudioDeviceMuted { kind, name, muted } => {
+                self.change_audio("mute the audio device", false, move |backend| {
+                    Box::pin(async move { backend.set_device_muted(kind, &name, muted).await })
+                });
+            }
+            SettingsAction::SetVmVolume { vm, volume } => {
+                self.change_audio("set the VM volume", false, move |backend| {
+                    Box::pin(async move { backend.set_vm_volume(&vm, volume).await })
+                });
+            }
+            SettingsAction::SetVmMuted { vm, muted } => {
+                self.change_audio("mute the VM", false, move |backend| {
+                    Box::pin(async move { backend.set_vm_muted(&vm, muted).await })
+                });
+            }
+            SettingsAction::NetworkRefreshRequest => self.refresh_network(),
+            SettingsAction::ScanWifi { interface } => self.scan_wifi(interface),
+            SettingsAction::ConnectWifi {
+                interface,
+                ssid,
+                password,
+            } => {
+                self.change_network(format!("join {ssid}"), move |backend| {
+                    Box::pin(
+                        async move { backend.connect_wifi(&interface, &ssid, &password).await },
+                    )
+                });
+            }
+            SettingsAction::DisconnectInterface { interface } => {
+                self.change_network(format!("disconnect {interface}"), move |backend| {
+                    Box::pin(async move { backend.disconnect(&interface).await })
+                });
+            }
+            SettingsAction::Power { action } => self.request_power_action(action),
             SettingsAction::ShowErrorPopup { message } => {
                 let popup = ErrorPopup::new(&message);
                 popup.set_transient_for(self.active_window().as_ref());
                 popup.set_modal(true);
                 popup.present();
             }
-            SettingsAction::OpenWireGuard { vm } => {
-                self.open_wireguard(&vm);
+            SettingsAction::LaunchCapability { vm, capability } => {
+                self.launch_capability(&vm, capability);
+            }
+            SettingsAction::LaunchWith { app } => {
+                self.show_launch_dialog(&app);
+            }
+            SettingsAction::MuteNotifications { vm, muted } => {
+                self.set_vm_muted(vm, muted);
             }
             SettingsAction::CheckForUpdateRequest => {
                 glib::spawn_future_local(glib::clone!(
                     #[strong(rename_to = app)]
                     self,
-                    async move { app.imp().service_model.check_for_update().await }
+                    async move {
+                        let updater = app.updater();
+                        match app.imp().service_model.get_sysinfo_status_from_host().await {
+                            Ok(status) => updater.set_current_version(status.ghaf_version),
+                            Err(e) => warn!("Failed to get the installed Ghaf version: {e}"),
+                        }
+                        updater.check().await;
+                    }
                 ));
             }
-            SettingsAction::UpdateRequest => {
-                self.imp().service_model.update_request();
-            }
         }
     }
 }
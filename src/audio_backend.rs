@@ -0,0 +1,591 @@
+use futures::future::LocalBoxFuture;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::process::Command;
+use thiserror::Error as ThisError;
+
+use crate::prelude::*;
+
+const PACTL: &str = "pactl";
+/// Volume of 100 % in PulseAudio units
+const VOLUME_NORM: f64 = 65536.;
+/// Stream properties naming the VM a stream comes from, in order of preference
+const VM_PROPERTIES: [&str; 2] = ["application.process.host", "application.name"];
+
+/// Whether a device plays or records sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "AudioDeviceKind")]
+#[repr(u8)]
+pub enum DeviceKind {
+    Output,
+    Input,
+}
+
+impl DeviceKind {
+    /// PulseAudio object type, used in `pactl` commands
+    fn pactl_name(self) -> &'static str {
+        match self {
+            Self::Output => "sink",
+            Self::Input => "source",
+        }
+    }
+}
+
+impl fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Output => "output",
+            Self::Input => "input",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioDevice {
+    /// Sound server name, e.g. `alsa_output.pci-0000_00_1f.3.analog-stereo`
+    pub name: String,
+    pub description: String,
+    /// From 0 to 1 for 100 %
+    pub volume: f64,
+    pub muted: bool,
+}
+
+/// Playback streams of one VM, set together
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmStreams {
+    pub vm: String,
+    /// Loudest stream of the VM, from 0 to 1 for 100 %
+    pub volume: f64,
+    /// Only when all its streams are muted
+    pub muted: bool,
+    streams: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioState {
+    pub outputs: Vec<AudioDevice>,
+    pub inputs: Vec<AudioDevice>,
+    pub default_output: Option<String>,
+    pub default_input: Option<String>,
+    pub streams: Vec<VmStreams>,
+}
+
+impl AudioState {
+    pub fn devices(&self, kind: DeviceKind) -> &[AudioDevice] {
+        match kind {
+            DeviceKind::Output => &self.outputs,
+            DeviceKind::Input => &self.inputs,
+        }
+    }
+
+    pub fn default_device(&self, kind: DeviceKind) -> Option<&AudioDevice> {
+        let name = match kind {
+            DeviceKind::Output => self.default_output.as_deref(),
+            DeviceKind::Input => self.default_input.as_deref(),
+        }?;
+        self.devices(kind).iter().find(|device| device.name == name)
+    }
+}
+
+#[derive(ThisError, Debug)]
+pub enum AudioError {
+    #[error("cannot run {PACTL}: {0}")]
+    Spawn(std::io::Error),
+    #[error("cannot follow {PACTL} events: {0}")]
+    Subscribe(glib::Error),
+    #[error("{PACTL} failed: {0}")]
+    Command(String),
+    #[error("unexpected {PACTL} output: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("unknown {kind} {name}")]
+    UnknownDevice { kind: DeviceKind, name: String },
+    #[error("no sound from {0}")]
+    UnknownVm(String),
+    #[error("audio task was interrupted")]
+    Interrupted,
+}
+
+/// Devices and VM streams of the sound server in the audio VM, or an in-memory stand-in for
+/// testing
+pub trait AudioBackend: fmt::Debug {
+    fn state(&self) -> LocalBoxFuture<'_, Result<AudioState, AudioError>>;
+
+    fn set_default_device<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>>;
+
+    fn set_device_volume<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>>;
+
+    fn set_device_muted<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>>;
+
+    /// Sets all the playback streams of `vm`
+    fn set_vm_volume<'a>(
+        &'a self,
+        vm: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>>;
+
+    /// Mutes the current streams of `vm`, and those it starts later while it stays muted
+    fn set_vm_muted<'a>(
+        &'a self,
+        vm: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>>;
+
+    /// Watches for new streams to keep muted VMs silent, until the sound server goes away
+    fn follow_streams(&self) -> LocalBoxFuture<'_, Result<(), AudioError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[derive(Deserialize)]
+struct ChannelVolume {
+    value: u32,
+}
+
+/// Sink, source or sink input as listed by `pactl --format=json`
+#[derive(Deserialize)]
+struct PactlObject {
+    index: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    mute: bool,
+    #[serde(default)]
+    volume: HashMap<String, ChannelVolume>,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+}
+
+impl PactlObject {
+    fn volume(&self) -> f64 {
+        let total: f64 = self
+            .volume
+            .values()
+            .map(|channel| f64::from(channel.value))
+            .sum();
+        // Lossless, there are at most a few dozen channels
+        #[allow(clippy::cast_precision_loss)]
+        let channels = self.volume.len().max(1) as f64;
+        total / channels / VOLUME_NORM
+    }
+
+    fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn into_device(self) -> AudioDevice {
+        AudioDevice {
+            volume: self.volume(),
+            muted: self.mute,
+            description: if self.description.is_empty() {
+                self.name.clone()
+            } else {
+                self.description
+            },
+            name: self.name,
+        }
+    }
+}
+
+fn percent(volume: f64) -> String {
+    format!("{:.0}%", volume.max(0.) * 100.)
+}
+
+/// PulseAudio, or PipeWire through its PulseAudio server, driven by `pactl`. Streams are
+/// attributed to the VM whose host name they report, falling back to their application name.
+#[derive(Debug, Default)]
+pub struct PulseAudioBackend {
+    /// Sound server of the audio VM, e.g. `tcp:audio-vm:4713`, the default one if unset
+    server: Option<String>,
+    /// VMs muted from the panel, the sound server only knows about streams
+    muted_vms: RefCell<BTreeSet<String>>,
+}
+
+impl PulseAudioBackend {
+    pub fn new(server: Option<String>) -> Self {
+        Self {
+            server,
+            muted_vms: RefCell::default(),
+        }
+    }
+
+    fn server_args(&self) -> Vec<String> {
+        self.server
+            .iter()
+            .flat_map(|server| ["--server".into(), server.clone()])
+            .collect()
+    }
+
+    async fn pactl(&self, args: Vec<String>) -> Result<String, AudioError> {
+        let mut command = Command::new(PACTL);
+        command.args(self.server_args()).args(&args);
+        debug!("Running {PACTL} {}", args.join(" "));
+        let output = gio::spawn_blocking(move || command.output())
+            .await
+            .map_err(|_| AudioError::Interrupted)?
+            .map_err(AudioError::Spawn)?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(AudioError::Command(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ))
+        }
+    }
+
+    async fn list(&self, kind: &str) -> Result<Vec<PactlObject>, AudioError> {
+        let json = self
+            .pactl(vec!["--format=json".into(), "list".into(), kind.into()])
+            .await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    async fn default_device(&self, kind: DeviceKind) -> Result<Option<String>, AudioError> {
+        let name = self
+            .pactl(vec![format!("get-default-{}", kind.pactl_name())])
+            .await?;
+        let name = name.trim();
+        Ok((!name.is_empty()).then(|| name.to_owned()))
+    }
+
+    async fn vm_streams(&self, vm: &str) -> Result<Vec<u32>, AudioError> {
+        let state = self.state().await?;
+        state
+            .streams
+            .into_iter()
+            .find(|streams| streams.vm == vm)
+            .map(|streams| streams.streams)
+            .ok_or_else(|| AudioError::UnknownVm(vm.into()))
+    }
+
+    /// Playback streams by VM, muting those started by muted VMs
+    async fn streams(&self) -> Result<Vec<VmStreams>, AudioError> {
+        let mut streams: BTreeMap<String, VmStreams> = BTreeMap::new();
+        for mut input in self.list("sink-inputs").await? {
+            let vm = VM_PROPERTIES
+                .iter()
+                .find_map(|key| input.property(key))
+                .unwrap_or("Unknown")
+                .to_owned();
+            if !input.mute && self.muted_vms.borrow().contains(&vm) {
+                self.pactl(vec![
+                    "set-sink-input-mute".into(),
+                    input.index.to_string(),
+                    "1".into(),
+                ])
+                .await?;
+                input.mute = true;
+            }
+            let volume = input.volume();
+            let entry = streams.entry(vm.clone()).or_insert_with(|| VmStreams {
+                vm,
+                volume: 0.,
+                muted: true,
+                streams: Vec::new(),
+            });
+            entry.volume = entry.volume.max(volume);
+            entry.muted &= input.mute;
+            entry.streams.push(input.index);
+        }
+        Ok(streams.into_values().collect())
+    }
+}
+
+impl AudioBackend for PulseAudioBackend {
+    fn state(&self) -> LocalBoxFuture<'_, Result<AudioState, AudioError>> {
+        Box::pin(async move {
+            let outputs = self.list("sinks").await?;
+            // Monitors only echo the outputs
+            let inputs = self
+                .list("sources")
+                .await?
+                .into_iter()
+                .filter(|source| !source.name.ends_with(".monitor"));
+
+            let streams = self.streams().await?;
+
+            Ok(AudioState {
+                outputs: outputs.into_iter().map(PactlObject::into_device).collect(),
+                inputs: inputs.map(PactlObject::into_device).collect(),
+                default_output: self.default_device(DeviceKind::Output).await?,
+                default_input: self.default_device(DeviceKind::Input).await?,
+                streams,
+            })
+        })
+    }
+
+    fn set_default_device<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            self.pactl(vec![
+                format!("set-default-{}", kind.pactl_name()),
+                name.into(),
+            ])
+            .await
+            .map(drop)
+        })
+    }
+
+    fn set_device_volume<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            self.pactl(vec![
+                format!("set-{}-volume", kind.pactl_name()),
+                name.into(),
+                percent(volume),
+            ])
+            .await
+            .map(drop)
+        })
+    }
+
+    fn set_device_muted<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            self.pactl(vec![
+                format!("set-{}-mute", kind.pactl_name()),
+                name.into(),
+                u8::from(muted).to_string(),
+            ])
+            .await
+            .map(drop)
+        })
+    }
+
+    fn set_vm_volume<'a>(
+        &'a self,
+        vm: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            for stream in self.vm_streams(vm).await? {
+                self.pactl(vec![
+                    "set-sink-input-volume".into(),
+                    stream.to_string(),
+                    percent(volume),
+                ])
+                .await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn set_vm_muted<'a>(
+        &'a self,
+        vm: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            if muted {
+                self.muted_vms.borrow_mut().insert(vm.into());
+            } else {
+                self.muted_vms.borrow_mut().remove(vm);
+            }
+            for stream in self.vm_streams(vm).await? {
+                self.pactl(vec![
+                    "set-sink-input-mute".into(),
+                    stream.to_string(),
+                    u8::from(muted).to_string(),
+                ])
+                .await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn follow_streams(&self) -> LocalBoxFuture<'_, Result<(), AudioError>> {
+        Box::pin(async move {
+            let argv: Vec<OsString> = std::iter::once(PACTL.into())
+                .chain(self.server_args().into_iter().map(Into::into))
+                .chain(std::iter::once("subscribe".into()))
+                .collect();
+            let argv: Vec<&OsStr> = argv.iter().map(OsString::as_os_str).collect();
+            let process = gio::Subprocess::newv(&argv, gio::SubprocessFlags::STDOUT_PIPE)
+                .map_err(AudioError::Subscribe)?;
+            let events = gio::DataInputStream::new(
+                &process.stdout_pipe().expect("stdout of pactl is piped"),
+            );
+            while let Some(event) = events
+                .read_line_utf8_future(glib::Priority::DEFAULT)
+                .await
+                .map_err(AudioError::Subscribe)?
+            {
+                // e.g. "Event 'new' on sink-input #42"
+                if event.contains("'new' on sink-input") && !self.muted_vms.borrow().is_empty() {
+                    self.streams().await?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// A few devices and VM streams kept in memory, for trying the Audio page without a sound
+/// server
+#[derive(Debug)]
+pub struct FakeAudioBackend {
+    state: RefCell<AudioState>,
+}
+
+impl Default for FakeAudioBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeAudioBackend {
+    pub fn new() -> Self {
+        let device = |name: &str, description: &str, volume| AudioDevice {
+            name: name.into(),
+            description: description.into(),
+            volume,
+            muted: false,
+        };
+        let vm = |vm: &str, volume, stream| VmStreams {
+            vm: vm.into(),
+            volume,
+            muted: false,
+            streams: vec![stream],
+        };
+        Self {
+            state: RefCell::new(AudioState {
+                outputs: vec![
+                    device("fake.speakers", "Speakers", 0.7),
+                    device("fake.headphones", "Headphones", 0.4),
+                ],
+                inputs: vec![device("fake.microphone", "Internal microphone", 0.8)],
+                default_output: Some("fake.speakers".into()),
+                default_input: Some("fake.microphone".into()),
+                streams: vec![
+                    vm("chrome-vm", 1., 0),
+                    vm("comms-vm", 0.8, 1),
+                    vm("business-vm", 0.6, 2),
+                ],
+            }),
+        }
+    }
+
+    fn with_device(
+        &self,
+        kind: DeviceKind,
+        name: &str,
+        f: impl FnOnce(&mut AudioDevice),
+    ) -> Result<(), AudioError> {
+        let mut state = self.state.borrow_mut();
+        let devices = match kind {
+            DeviceKind::Output => &mut state.outputs,
+            DeviceKind::Input => &mut state.inputs,
+        };
+        let device = devices
+            .iter_mut()
+            .find(|device| device.name == name)
+            .ok_or_else(|| AudioError::UnknownDevice {
+                kind,
+                name: name.into(),
+            })?;
+        f(device);
+        Ok(())
+    }
+
+    fn with_vm(&self, vm: &str, f: impl FnOnce(&mut VmStreams)) -> Result<(), AudioError> {
+        let mut state = self.state.borrow_mut();
+        let streams = state
+            .streams
+            .iter_mut()
+            .find(|streams| streams.vm == vm)
+            .ok_or_else(|| AudioError::UnknownVm(vm.into()))?;
+        f(streams);
+        Ok(())
+    }
+}
+
+impl AudioBackend for FakeAudioBackend {
+    fn state(&self) -> LocalBoxFuture<'_, Result<AudioState, AudioError>> {
+        Box::pin(async { Ok(self.state.borrow().clone()) })
+    }
+
+    fn set_default_device<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move {
+            self.with_device(kind, name, |_| ())?;
+            let mut state = self.state.borrow_mut();
+            let default = match kind {
+                DeviceKind::Output => &mut state.default_output,
+                DeviceKind::Input => &mut state.default_input,
+            };
+            *default = Some(name.into());
+            Ok(())
+        })
+    }
+
+    fn set_device_volume<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move { self.with_device(kind, name, |device| device.volume = volume) })
+    }
+
+    fn set_device_muted<'a>(
+        &'a self,
+        kind: DeviceKind,
+        name: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move { self.with_device(kind, name, |device| device.muted = muted) })
+    }
+
+    fn set_vm_volume<'a>(
+        &'a self,
+        vm: &'a str,
+        volume: f64,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move { self.with_vm(vm, |streams| streams.volume = volume) })
+    }
+
+    fn set_vm_muted<'a>(
+        &'a self,
+        vm: &'a str,
+        muted: bool,
+    ) -> LocalBoxFuture<'a, Result<(), AudioError>> {
+        Box::pin(async move { self.with_vm(vm, |streams| streams.muted = muted) })
+    }
+}
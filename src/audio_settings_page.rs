@@ -0,0 +1,311 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::audio_backend::{AudioState, DeviceKind, VmStreams};
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{CompositeTemplate, DropDown, Label, ListBox, Scale, ToggleButton, glib};
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use crate::audio_backend::{AudioState, DeviceKind};
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/audio_settings_page.ui")]
+    pub struct AudioSettingsPage {
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub output_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub output_volume_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub output_mute_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub input_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub input_volume_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub input_mute_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub no_streams_label: TemplateChild<Label>,
+        #[template_child]
+        pub streams_list: TemplateChild<ListBox>,
+
+        pub(super) state: RefCell<AudioState>,
+        /// Set while the widgets follow a new state, which must not be sent back
+        pub(super) updating: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AudioSettingsPage {
+        const NAME: &'static str = "AudioSettingsPage";
+        type Type = super::AudioSettingsPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl AudioSettingsPage {
+        #[template_callback]
+        fn on_output_selected(&self) {
+            self.select_device(DeviceKind::Output, self.output_switch.selected());
+        }
+
+        #[template_callback]
+        fn on_input_selected(&self) {
+            self.select_device(DeviceKind::Input, self.input_switch.selected());
+        }
+
+        #[template_callback]
+        fn on_output_volume_changed(&self) {
+            let volume = self.output_volume_scale.value();
+            self.emit_device_change("device-volume-changed", DeviceKind::Output, &volume);
+        }
+
+        #[template_callback]
+        fn on_input_volume_changed(&self) {
+            let volume = self.input_volume_scale.value();
+            self.emit_device_change("device-volume-changed", DeviceKind::Input, &volume);
+        }
+
+        #[template_callback]
+        fn on_output_mute_toggled(&self) {
+            let muted = self.output_mute_button.is_active();
+            self.output_volume_scale.set_sensitive(!muted);
+            self.emit_device_change("device-muted-changed", DeviceKind::Output, &muted);
+        }
+
+        #[template_callback]
+        fn on_input_mute_toggled(&self) {
+            let muted = self.input_mute_button.is_active();
+            self.input_volume_scale.set_sensitive(!muted);
+            self.emit_device_change("device-muted-changed", DeviceKind::Input, &muted);
+        }
+    }
+
+    impl AudioSettingsPage {
+        fn select_device(&self, kind: DeviceKind, index: u32) {
+            if self.updating.get() {
+                return;
+            }
+            let Some(name) = self
+                .state
+                .borrow()
+                .devices(kind)
+                .get(index as usize)
+                .map(|device| device.name.clone())
+            else {
+                return;
+            };
+            self.obj()
+                .emit_by_name::<()>("default-device-changed", &[&kind, &name]);
+        }
+
+        /// Changes apply to the default device of `kind`
+        fn emit_device_change(&self, signal: &str, kind: DeviceKind, value: &dyn ToValue) {
+            if self.updating.get() {
+                return;
+            }
+            let Some(name) = self
+                .state
+                .borrow()
+                .default_device(kind)
+                .map(|device| device.name.clone())
+            else {
+                return;
+            };
+            self.obj()
+                .emit_by_name::<()>(signal, &[&kind, &name, value]);
+        }
+    }
+
+    impl ObjectImpl for AudioSettingsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            // Devices and VM streams come and go, so they are read again whenever shown
+            self.obj()
+                .connect_map(|page| page.emit_by_name::<()>("refresh", &[]));
+            self.obj().set_state(AudioState::default());
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("refresh").build(),
+                    // Kind and name of the device
+                    Signal::builder("default-device-changed")
+                        .param_types([DeviceKind::static_type(), String::static_type()])
+                        .build(),
+                    // Kind and name of the device, volume from 0 to 1
+                    Signal::builder("device-volume-changed")
+                        .param_types([
+                            DeviceKind::static_type(),
+                            String::static_type(),
+                            f64::static_type(),
+                        ])
+                        .build(),
+                    Signal::builder("device-muted-changed")
+                        .param_types([
+                            DeviceKind::static_type(),
+                            String::static_type(),
+                            bool::static_type(),
+                        ])
+                        .build(),
+                    // VM name, volume from 0 to 1
+                    Signal::builder("vm-volume-changed")
+                        .param_types([String::static_type(), f64::static_type()])
+                        .build(),
+                    Signal::builder("vm-muted-changed")
+                        .param_types([String::static_type(), bool::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for AudioSettingsPage {}
+    impl BoxImpl for AudioSettingsPage {}
+}
+
+glib::wrapper! {
+pub struct AudioSettingsPage(ObjectSubclass<imp::AudioSettingsPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for AudioSettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioSettingsPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_state(&self, state: AudioState) {
+        let imp = self.imp();
+        imp.updating.set(true);
+        for (kind, switch, scale, mute_button) in [
+            (
+                DeviceKind::Output,
+                &imp.output_switch,
+                &imp.output_volume_scale,
+                &imp.output_mute_button,
+            ),
+            (
+                DeviceKind::Input,
+                &imp.input_switch,
+                &imp.input_volume_scale,
+                &imp.input_mute_button,
+            ),
+        ] {
+            let devices = state.devices(kind);
+            let labels: Vec<&str> = devices
+                .iter()
+                .map(|device| device.description.as_str())
+                .collect();
+            switch.set_model(Some(&gtk::StringList::new(&labels)));
+            switch.set_sensitive(!devices.is_empty());
+            let default = state.default_device(kind);
+            if let Some(index) = default
+                .and_then(|default| devices.iter().position(|device| device == default))
+                .and_then(|index| u32::try_from(index).ok())
+            {
+                switch.set_selected(index);
+            }
+            scale.set_value(default.map_or(0., |device| device.volume));
+            scale.set_sensitive(default.is_some_and(|device| !device.muted));
+            mute_button.set_active(default.is_some_and(|device| device.muted));
+            mute_button.set_sensitive(default.is_some());
+        }
+
+        imp.streams_list.remove_all();
+        for streams in &state.streams {
+            imp.streams_list.append(&self.stream_row(streams));
+        }
+        imp.streams_list.set_visible(!state.streams.is_empty());
+        imp.no_streams_label.set_visible(state.streams.is_empty());
+        imp.state.replace(state);
+        imp.updating.set(false);
+    }
+
+    /// Shown above the devices until the next successful read
+    pub fn set_error(&self, error: Option<&str>) {
+        let label = &self.imp().status_label;
+        label.set_label(error.unwrap_or_default());
+        label.set_visible(error.is_some());
+    }
+
+    fn stream_row(&self, streams: &VmStreams) -> gtk::Box {
+        let row = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(10)
+            .margin_end(10)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+        let name = gtk::Label::builder()
+            .label(&streams.vm)
+            .xalign(0.)
+            .width_chars(14)
+            .build();
+        let scale = gtk::Scale::with_range(gtk::Orientation::Horizontal, 0., 1., 0.05);
+        scale.set_hexpand(true);
+        scale.set_draw_value(false);
+        scale.set_value(streams.volume);
+        scale.set_sensitive(!streams.muted);
+        let mute_button = gtk::ToggleButton::builder()
+            .icon_name("audio-volume-muted-symbolic")
+            .tooltip_text(format!("Mute {}", streams.vm))
+            .valign(gtk::Align::Center)
+            .active(streams.muted)
+            .build();
+
+        let vm = streams.vm.clone();
+        scale.connect_value_changed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[strong]
+            vm,
+            move |scale| {
+                if !page.imp().updating.get() {
+                    page.emit_by_name::<()>("vm-volume-changed", &[&vm, &scale.value()]);
+                }
+            }
+        ));
+        mute_button.connect_toggled(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[weak]
+            scale,
+            move |button| {
+                let muted = button.is_active();
+                scale.set_sensitive(!muted);
+                if !page.imp().updating.get() {
+                    page.emit_by_name::<()>("vm-muted-changed", &[&vm, &muted]);
+                }
+            }
+        ));
+
+        row.append(&name);
+        row.append(&scale);
+        row.append(&mute_button);
+        row
+    }
+}
@@ -0,0 +1,105 @@
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::certificate::{self, CertificateInfo};
+use crate::config::TlsSettings;
+
+/// Certificates currently on disk
+#[derive(Debug, Clone)]
+pub struct CertStatus {
+    pub ca: Result<CertificateInfo, String>,
+    pub cert: Result<CertificateInfo, String>,
+}
+
+impl CertStatus {
+    fn read(tls: &TlsSettings) -> Self {
+        Self {
+            ca: certificate::inspect_ca(&tls.ca_cert).map_err(|e| e.to_string()),
+            cert: certificate::inspect_cert_and_key(&tls.cert, &tls.key).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Warning text if a certificate expired or expires within `warning_days`
+    pub fn expiry_warning(&self, warning_days: u32) -> Option<String> {
+        [
+            ("Client certificate", &self.cert),
+            ("CA certificate", &self.ca),
+        ]
+        .into_iter()
+        .find_map(|(what, info)| {
+            let info = info.as_ref().ok()?;
+            let days = info.days_left()?;
+            let date = info.expiry_date().unwrap_or_default();
+            if days < 0 {
+                Some(format!("{what} expired on {date}"))
+            } else if days < i64::from(warning_days) {
+                Some(format!("{what} expires in {days} days ({date})"))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+mod imp {
+    use gtk::glib;
+    use gtk::subclass::prelude::*;
+    use std::cell::RefCell;
+
+    use crate::config::TlsSettings;
+    use crate::file_watcher::FileWatcher;
+
+    #[derive(Debug, Default)]
+    pub struct CertMonitor {
+        pub(super) tls: RefCell<Option<TlsSettings>>,
+        pub(super) watcher: FileWatcher,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CertMonitor {
+        const NAME: &'static str = "CertMonitor";
+        type Type = super::CertMonitor;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for CertMonitor {}
+}
+
+glib::wrapper! {
+    pub struct CertMonitor(ObjectSubclass<imp::CertMonitor>);
+}
+
+impl Default for CertMonitor {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl CertMonitor {
+    /// Watches the given certificate files, replacing any previous ones.
+    /// Nothing is watched when TLS is disabled.
+    pub fn watch(&self, tls: &TlsSettings) {
+        let imp = self.imp();
+        imp.tls.replace(tls.enable.then(|| tls.clone()));
+        let paths = if tls.enable {
+            vec![tls.ca_cert.as_path(), tls.cert.as_path(), tls.key.as_path()]
+        } else {
+            Vec::new()
+        };
+        // The certificates are read on demand, any change to their files is one
+        imp.watcher.watch(&paths, || true);
+    }
+
+    /// Reads the watched certificates, `None` when TLS is disabled
+    pub fn status(&self) -> Option<CertStatus> {
+        self.imp().tls.borrow().as_ref().map(CertStatus::read)
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.imp().watcher.connect_changed(glib::clone!(
+            #[weak(rename_to = monitor)]
+            self,
+            move |_| f(&monitor)
+        ))
+    }
+}
@@ -0,0 +1,68 @@
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::path::Path;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Details of a PEM certificate shown to the user
+#[derive(Debug, Clone)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub not_valid_after: Option<glib::DateTime>,
+}
+
+impl CertificateInfo {
+    fn new(certificate: &gio::TlsCertificate) -> Self {
+        Self {
+            subject: certificate
+                .subject_name()
+                .map_or_else(|| "unknown subject".into(), Into::into),
+            not_valid_after: certificate.not_valid_after(),
+        }
+    }
+
+    /// Whole days until expiry, negative once expired
+    pub fn days_left(&self) -> Option<i64> {
+        let now = glib::DateTime::now_utc().ok()?;
+        let expiry = self.not_valid_after.as_ref()?;
+        Some((expiry.to_unix() - now.to_unix()).div_euclid(SECONDS_PER_DAY))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.days_left().is_some_and(|days| days < 0)
+    }
+
+    pub fn expiry_date(&self) -> Option<String> {
+        self.not_valid_after
+            .as_ref()
+            .and_then(|date| date.format("%Y-%m-%d").ok())
+            .map(Into::into)
+    }
+
+    /// One line summary, e.g. `CN=admin-vm, expires 2027-01-31`
+    pub fn summary(&self) -> String {
+        match (self.expiry_date(), self.is_expired()) {
+            (Some(date), true) => format!("{subject}, expired {date}", subject = self.subject),
+            (Some(date), false) => format!("{subject}, expires {date}", subject = self.subject),
+            (None, _) => self.subject.clone(),
+        }
+    }
+}
+
+/// Reads the CA bundle, returning the first certificate of the chain
+pub fn inspect_ca(path: &Path) -> Result<CertificateInfo, glib::Error> {
+    gio::TlsCertificate::list_new_from_file(path)?
+        .first()
+        .map(CertificateInfo::new)
+        .ok_or_else(|| {
+            glib::Error::new(
+                gio::TlsError::BadCertificate,
+                &format!("No certificate found in {path}", path = path.display()),
+            )
+        })
+}
+
+/// Reads the client certificate, checking that the private key matches it
+pub fn inspect_cert_and_key(cert: &Path, key: &Path) -> Result<CertificateInfo, glib::Error> {
+    gio::TlsCertificate::from_files(cert, key).map(|certificate| CertificateInfo::new(&certificate))
+}
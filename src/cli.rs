@@ -0,0 +1,295 @@
+use clap::Subcommand;
+use givc_common::query::TrustLevel;
+use gtk::glib;
+use serde::Serialize;
+use thiserror::Error as ThisError;
+
+use crate::config::Config;
+use crate::control_action::ControlAction;
+use crate::prelude::*;
+use crate::service_gobject::ServiceGObject;
+use crate::service_model::{HostSysinfoStatus, ServiceModel, StatsResponse};
+
+/// Exit status when an admin service request failed
+const EXIT_RPC_FAILED: i32 = 1;
+/// Exit status when none of the admin service endpoints answered
+const EXIT_NOT_CONNECTED: i32 = 3;
+/// Exit status when the given unit or VM is not known to the admin service
+const EXIT_UNKNOWN_UNIT: i32 = 4;
+
+/// Headless commands, run against the admin service without starting the GUI
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List VMs, apps and services
+    List,
+    /// Start a unit, VMs may be given by their VM name
+    Start { unit: String },
+    /// Stop a unit
+    Stop { unit: String },
+    /// Pause a unit
+    Pause { unit: String },
+    /// Resume a paused unit
+    Resume { unit: String },
+    /// Print memory and CPU statistics of a VM
+    Stats {
+        vm: String,
+        /// Print statistics every second until interrupted
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Print the host Ghaf version and security status
+    Sysinfo,
+    /// Set the system locale, e.g. `en_US.UTF-8`
+    SetLocale { locale: String },
+    /// Set the system timezone, e.g. `Europe/Helsinki`
+    SetTimezone { timezone: String },
+}
+
+#[derive(ThisError, Debug)]
+enum CliError {
+    #[error(transparent)]
+    NotConnected(anyhow::Error),
+    #[error("unknown unit {0}")]
+    UnknownUnit(String),
+    #[error(transparent)]
+    Rpc(#[from] anyhow::Error),
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::NotConnected(_) => EXIT_NOT_CONNECTED,
+            Self::UnknownUnit(_) => EXIT_UNKNOWN_UNIT,
+            Self::Rpc(_) => EXIT_RPC_FAILED,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Unit {
+    name: String,
+    display_name: String,
+    kind: &'static str,
+    status: &'static str,
+    vm: String,
+    trust: &'static str,
+}
+
+impl From<&ServiceGObject> for Unit {
+    fn from(obj: &ServiceGObject) -> Self {
+        Self {
+            name: obj.name(),
+            display_name: obj.display_name(),
+            kind: if obj.is_vm() {
+                "vm"
+            } else if obj.is_app() {
+                "app"
+            } else {
+                "service"
+            },
+            status: obj.status_name(),
+            vm: obj.vm_name(),
+            trust: match obj.trust_level() {
+                TrustLevel::Secure => "secure",
+                TrustLevel::Warning => "warning",
+                TrustLevel::NotSecure => "not-secure",
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ActionResult {
+    unit: String,
+    action: &'static str,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    vm: String,
+    memory_total: Option<u64>,
+    memory_free: Option<u64>,
+    memory_available: Option<u64>,
+    user_cycles: Option<u64>,
+    sys_cycles: Option<u64>,
+    total_cycles: Option<u64>,
+}
+
+impl Stats {
+    fn new(vm: &str, stats: &StatsResponse) -> Self {
+        Self {
+            vm: vm.into(),
+            memory_total: stats.memory.as_ref().map(|memory| memory.total),
+            memory_free: stats.memory.as_ref().map(|memory| memory.free),
+            memory_available: stats.memory.as_ref().map(|memory| memory.available),
+            user_cycles: stats.process.as_ref().map(|process| process.user_cycles),
+            sys_cycles: stats.process.as_ref().map(|process| process.sys_cycles),
+            total_cycles: stats.process.as_ref().map(|process| process.total_cycles),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn print(&self) {
+        let mib =
+            |bytes: Option<u64>| bytes.map_or_else(|| "-".into(), |b| format!("{} MiB", b >> 20));
+        let cpu = match (self.user_cycles, self.sys_cycles, self.total_cycles) {
+            (Some(user), Some(sys), Some(total)) if total > 0 => format!(
+                "user {:.1}%, system {:.1}%",
+                user as f64 * 100. / total as f64,
+                sys as f64 * 100. / total as f64
+            ),
+            _ => "-".into(),
+        };
+        println!(
+            "{vm}: memory total {total}, free {free}, available {available}; CPU {cpu}",
+            vm = self.vm,
+            total = mib(self.memory_total),
+            free = mib(self.memory_free),
+            available = mib(self.memory_available),
+        );
+    }
+}
+
+struct Cli {
+    model: ServiceModel,
+    json: bool,
+}
+
+/// Connects to the startup profile, runs `command` and returns the process exit status
+pub fn run(command: Command, config: &Config, json: bool) -> i32 {
+    let profile = config.startup_profile();
+    let model = ServiceModel::default();
+    model.set_tls_info(profile.tls.tls_info());
+    model.set_endpoints(profile.admin.endpoints());
+
+    let cli = Cli { model, json };
+    let result = glib::MainContext::default().block_on(async move {
+        cli.model
+            .wait_connected()
+            .await
+            .map_err(CliError::NotConnected)?;
+        cli.execute(command).await
+    });
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("ctrl-panel: {e}");
+            e.exit_code()
+        }
+    }
+}
+
+impl Cli {
+    async fn execute(&self, command: Command) -> Result<(), CliError> {
+        match command {
+            Command::List => {
+                self.list();
+                Ok(())
+            }
+            Command::Start { unit } => self.control(ControlAction::Start, &unit).await,
+            Command::Stop { unit } => self.control(ControlAction::Shutdown, &unit).await,
+            Command::Pause { unit } => self.control(ControlAction::Pause, &unit).await,
+            Command::Resume { unit } => self.control(ControlAction::Resume, &unit).await,
+            Command::Stats { vm, watch } => self.stats(&vm, watch).await,
+            Command::Sysinfo => {
+                let status = self.model.get_sysinfo_status_from_host().await?;
+                self.print_sysinfo(&status);
+                Ok(())
+            }
+            Command::SetLocale { locale } => Ok(self.model.set_locale(locale).await?),
+            Command::SetTimezone { timezone } => Ok(self.model.set_timezone(timezone).await?),
+        }
+    }
+
+    fn units(&self) -> Vec<ServiceGObject> {
+        self.model.wrap::<ServiceGObject>().iter().collect()
+    }
+
+    fn list(&self) {
+        let units: Vec<Unit> = self.units().iter().map(Unit::from).collect();
+        if self.json {
+            print_json(&units);
+            return;
+        }
+        for unit in units {
+            println!(
+                "{name:<48} {kind:<8} {status:<12} {vm}",
+                name = unit.name,
+                kind = unit.kind,
+                status = unit.status,
+                vm = unit.vm
+            );
+        }
+    }
+
+    /// Finds a unit by its name, or a VM by its VM name
+    fn find_unit(&self, unit: &str) -> Result<ServiceGObject, CliError> {
+        let units = self.units();
+        units
+            .iter()
+            .find(|obj| obj.name() == unit)
+            .or_else(|| {
+                units
+                    .iter()
+                    .find(|obj| obj.is_vm() && obj.vm_name() == unit)
+            })
+            .cloned()
+            .ok_or_else(|| CliError::UnknownUnit(unit.into()))
+    }
+
+    async fn control(&self, action: ControlAction, unit: &str) -> Result<(), CliError> {
+        let obj = self.find_unit(unit)?;
+        self.model.control_service(action, &obj).await?;
+        if self.json {
+            print_json(&ActionResult {
+                unit: obj.name(),
+                action: action.label(),
+            });
+        } else {
+            println!(
+                "{action}: {name}",
+                action = action.label(),
+                name = obj.name()
+            );
+        }
+        Ok(())
+    }
+
+    async fn stats(&self, vm: &str, watch: bool) -> Result<(), CliError> {
+        loop {
+            let stats = Stats::new(vm, &self.model.get_stats(vm.into()).await?);
+            match (self.json, watch) {
+                // One object per line, so that the output can be streamed
+                (true, true) => println!("{}", serde_json::to_string(&stats).unwrap_or_default()),
+                (true, false) => print_json(&stats),
+                (false, _) => stats.print(),
+            }
+            if !watch {
+                return Ok(());
+            }
+            glib::timeout_future_seconds(1).await;
+        }
+    }
+
+    fn print_sysinfo(&self, sysinfo: &HostSysinfoStatus) {
+        if self.json {
+            print_json(sysinfo);
+            return;
+        }
+        let yes_no = |value: Option<bool>| match value {
+            Some(true) => "enabled",
+            Some(false) => "disabled",
+            None => "unknown",
+        };
+        println!("Ghaf version:    {}", sysinfo.ghaf_version);
+        println!("Secure boot:     {}", yes_no(sysinfo.secure_boot));
+        println!("Disk encryption: {}", yes_no(sysinfo.disk_encryption));
+    }
+}
+
+fn print_json(value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("ctrl-panel: {e}"),
+    }
+}
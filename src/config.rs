@@ -0,0 +1,695 @@
+use chrono::NaiveTime;
+use givc_client::endpoint::TlsConfig;
+use gtk::glib;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error as ThisError;
+
+use crate::LogOutput;
+use crate::endpoint::Endpoint;
+use crate::prelude::*;
+
+pub const SYSTEM_CONFIG_FILE: &str = "/etc/ctrl-panel/config.toml";
+
+/// Profile made of the top-level `[admin]` and `[tls]` sections
+pub const DEFAULT_PROFILE: &str = "default";
+
+const VM_CAPABILITIES_FILE: &str = "/etc/ctrl-panel/vm-capabilities.toml";
+const LEGACY_WIREGUARD_VMS_FILE: &str = "/etc/ctrl-panel/wireguard-gui-vms.txt";
+
+#[derive(ThisError, Debug)]
+pub enum ConfigError {
+    #[error("cannot read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot write {}: {source}", .path.display())]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{}: {source}", .path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("invalid configuration: {0}")]
+    Schema(#[from] toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+    #[error("{0}.addr must not be empty")]
+    EmptyAddress(String),
+    #[error("{0}.port must not be 0")]
+    InvalidPort(String),
+    #[error("{0}.{1} must be set when TLS is enabled")]
+    MissingTlsSetting(String, &'static str),
+    #[error("unknown connection profile \"{0}\"")]
+    UnknownProfile(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    pub addr: String,
+    pub port: u16,
+    /// Endpoint URI, takes precedence over `addr` and `port`
+    pub endpoint: Option<Endpoint>,
+    /// Tried in order when the admin service does not answer on the primary endpoint
+    pub failover: Vec<Endpoint>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            addr: "192.168.101.10".into(),
+            port: 9001,
+            endpoint: None,
+            failover: Vec::new(),
+        }
+    }
+}
+
+impl AdminConfig {
+    /// Endpoint connected to first
+    pub fn primary(&self) -> Endpoint {
+        self.endpoint.clone().unwrap_or_else(|| Endpoint::Tcp {
+            addr: self.addr.clone(),
+            port: self.port,
+        })
+    }
+
+    /// Primary endpoint followed by the failover ones
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        std::iter::once(self.primary())
+            .chain(self.failover.iter().cloned())
+            .collect()
+    }
+
+    /// Failover endpoints are checked when parsed, only `addr` and `port` can be invalid
+    fn validate(&self, section: &str) -> Result<(), ConfigError> {
+        match self.primary() {
+            Endpoint::Tcp { addr, .. } if addr.trim().is_empty() => {
+                Err(ConfigError::EmptyAddress(section.into()))
+            }
+            Endpoint::Tcp { port: 0, .. } => Err(ConfigError::InvalidPort(section.into())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, glib::Boxed)]
+#[boxed_type(name = "TlsSettings")]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TlsSettings {
+    pub enable: bool,
+    /// TLS server name of the admin service
+    pub name: String,
+    pub ca_cert: PathBuf,
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl Default for TlsSettings {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            name: "admin-vm".into(),
+            ca_cert: "/run/givc/ca-cert.pem".into(),
+            cert: "/run/givc/cert.pem".into(),
+            key: "/run/givc/key.pem".into(),
+        }
+    }
+}
+
+impl TlsSettings {
+    pub fn tls_info(&self) -> Option<(String, TlsConfig)> {
+        self.enable.then(|| {
+            (
+                self.name.clone(),
+                TlsConfig {
+                    ca_cert_file_path: self.ca_cert.clone(),
+                    cert_file_path: self.cert.clone(),
+                    key_file_path: self.key.clone(),
+                    tls_name: Some(self.name.clone()),
+                },
+            )
+        })
+    }
+
+    fn validate(&self, section: &str) -> Result<(), ConfigError> {
+        if !self.enable {
+            return Ok(());
+        }
+        if self.name.is_empty() {
+            return Err(ConfigError::MissingTlsSetting(section.into(), "name"));
+        }
+        for (key, path) in [
+            ("ca-cert", &self.ca_cert),
+            ("cert", &self.cert),
+            ("key", &self.key),
+        ] {
+            if path.as_os_str().is_empty() {
+                return Err(ConfigError::MissingTlsSetting(section.into(), key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[profiles.<name>]` entry, without `tls` the top-level `[tls]` section is used
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub admin: AdminConfig,
+    #[serde(default)]
+    pub tls: Option<TlsSettings>,
+}
+
+/// Connection settings of one profile, with the TLS section resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub admin: AdminConfig,
+    pub tls: TlsSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CertificatesConfig {
+    /// Warn when the client or CA certificate expires within this many days
+    pub expiry_warning_days: u32,
+}
+
+impl Default for CertificatesConfig {
+    fn default() -> Self {
+        Self {
+            expiry_warning_days: 14,
+        }
+    }
+}
+
+/// Which lifecycle events are notified, see `notifications`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NotificationsConfig {
+    pub vm_down: bool,
+    pub vm_paused: bool,
+    pub app_stopped: bool,
+    pub trust_dropped: bool,
+    /// VM names whose events, including those of their apps, are not notified
+    pub muted_vms: BTreeSet<String>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            vm_down: true,
+            vm_paused: true,
+            app_stopped: true,
+            trust_dropped: true,
+            muted_vms: BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FilesConfig {
+    /// Per-VM capabilities, see `vm_capabilities`
+    pub capabilities: Option<PathBuf>,
+    /// Launchable apps, see `app_catalog`
+    pub app_catalog: PathBuf,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            capabilities: None,
+            app_catalog: "/etc/ctrl-panel/app-catalog.toml".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UpdatesConfig {
+    /// Directory of release manifests used instead of the admin service, see `update_backend`
+    pub manifest_dir: Option<PathBuf>,
+}
+
+/// Hour format of the times shown by the panel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ClockFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    Hours24,
+    #[serde(rename = "12h")]
+    Hours12,
+}
+
+impl ClockFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hours24 => "24h",
+            Self::Hours12 => "12h",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClockConfig {
+    pub format: ClockFormat,
+}
+
+/// Warmer screen colours from `start` to `end`, local times written "HH:MM"
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NightLightConfig {
+    pub enabled: bool,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub end: NaiveTime,
+    /// Colour temperature in Kelvin while active
+    pub temperature: u32,
+}
+
+impl Default for NightLightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: NaiveTime::from_hms_opt(20, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or_default(),
+            temperature: 4000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DisplayConfig {
+    /// In-memory outputs and backlight instead of the real ones, see `display_backend`
+    pub fake: bool,
+    pub night_light: NightLightConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct AudioConfig {
+    /// In-memory devices and VM streams instead of the sound server, see `audio_backend`
+    pub fake: bool,
+    /// PulseAudio server of the audio VM, e.g. "tcp:audio-vm:4713", the default one if unset
+    pub server: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NetworkConfig {
+    /// In-memory interfaces and Wi-Fi networks instead of NetworkManager, see `network_backend`
+    pub fake: bool,
+    /// D-Bus address of the net-vm system bus forwarded by the admin service,
+    /// e.g. "unix:path=/tmp/dbusproxy_net.sock", the local system bus if unset
+    pub bus_address: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    #[serde(deserialize_with = "deserialize_level")]
+    pub level: log::Level,
+    pub output: LogOutput,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: log::Level::Info,
+            output: LogOutput::default(),
+        }
+    }
+}
+
+fn deserialize_level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<log::Level, D::Error> {
+    let level = String::deserialize(deserializer)?;
+    level.parse().map_err(serde::de::Error::custom)
+}
+
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+    let time = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&time, "%H:%M").map_err(serde::de::Error::custom)
+}
+
+/// Panel configuration, merged from the system file, the user file and command line options,
+/// in increasing order of precedence:
+///
+/// ```toml
+/// # Profile to connect to on startup
+/// default-profile = "default"
+///
+/// [admin]
+/// addr = "192.168.101.10"
+/// port = 9001
+/// # tcp://host:port, unix:///path or vsock://cid:port, replaces addr and port
+/// # endpoint = "vsock://2:9001"
+/// failover = ["tcp://192.168.101.11:9001"]
+///
+/// [tls]
+/// enable = true
+/// name = "admin-vm"
+/// ca-cert = "/run/givc/ca-cert.pem"
+/// cert = "/run/givc/cert.pem"
+/// key = "/run/givc/key.pem"
+///
+/// [certificates]
+/// expiry-warning-days = 14
+///
+/// [notifications]
+/// vm-down = true
+/// vm-paused = true
+/// app-stopped = true
+/// trust-dropped = true
+/// muted-vms = ["chrome-vm"]
+///
+/// [files]
+/// capabilities = "/etc/ctrl-panel/vm-capabilities.toml"
+/// app-catalog = "/etc/ctrl-panel/app-catalog.toml"
+///
+/// [updates]
+/// # Releases from local manifests instead of the admin service, for testing
+/// # manifest-dir = "/var/lib/ctrl-panel/update-manifests"
+///
+/// [clock]
+/// # "24h" or "12h", also set on the Date & Time page
+/// format = "24h"
+///
+/// [display]
+/// # In-memory outputs and backlight, for testing without real hardware
+/// # fake = true
+///
+/// [display.night-light]
+/// enabled = false
+/// start = "20:00"
+/// end = "07:00"
+/// temperature = 4000
+///
+/// [audio]
+/// # Sound server of the audio VM, the default PulseAudio server if unset
+/// server = "tcp:audio-vm:4713"
+/// # In-memory devices and VM streams, for testing without a sound server
+/// # fake = true
+///
+/// [network]
+/// # System bus of the net-vm forwarded by the admin service, the local one if unset
+/// bus-address = "unix:path=/tmp/dbusproxy_net.sock"
+/// # In-memory interfaces and Wi-Fi networks, for testing without NetworkManager
+/// # fake = true
+///
+/// [log]
+/// level = "info"
+/// output = "syslog"
+///
+/// # Further connection profiles, selectable in the header bar
+/// [profiles.lab.admin]
+/// addr = "10.0.0.5"
+/// port = 9001
+/// failover = ["tcp://10.0.0.6:9001"]
+/// ```
+///
+/// The top-level `[admin]` and `[tls]` sections form the `default` profile, other profiles
+/// use the top-level `[tls]` section unless they have their own.
+/// The `[github]` table of the user file belongs to the bug reporter, see `github`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    pub admin: AdminConfig,
+    pub tls: TlsSettings,
+    pub certificates: CertificatesConfig,
+    pub notifications: NotificationsConfig,
+    pub files: FilesConfig,
+    pub updates: UpdatesConfig,
+    pub clock: ClockConfig,
+    pub display: DisplayConfig,
+    pub audio: AudioConfig,
+    pub network: NetworkConfig,
+    pub log: LogConfig,
+    /// Profile connected to on startup, [`DEFAULT_PROFILE`] if unset
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl Config {
+    pub fn user_config_path() -> PathBuf {
+        crate::xdg::config_dir().join("config.toml")
+    }
+
+    /// Reads and merges the system and user layers
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut table = toml::Table::new();
+        for path in [PathBuf::from(SYSTEM_CONFIG_FILE), Self::user_config_path()] {
+            if let Some(layer) = read_layer(&path)? {
+                merge(&mut table, layer);
+            }
+        }
+        Ok(table.try_into()?)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.admin.validate("admin")?;
+        self.tls.validate("tls")?;
+        for (name, profile) in &self.profiles {
+            profile.admin.validate(&format!("profiles.{name}.admin"))?;
+            if let Some(tls) = &profile.tls {
+                tls.validate(&format!("profiles.{name}.tls"))?;
+            }
+        }
+        let default = self.default_profile_name();
+        if self.profile(default).is_none() {
+            return Err(ConfigError::UnknownProfile(default.into()));
+        }
+        Ok(())
+    }
+
+    fn default_profile_name(&self) -> &str {
+        self.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// The default profile first, then the `[profiles]` entries in name order
+    pub fn profile_names(&self) -> Vec<String> {
+        std::iter::once(DEFAULT_PROFILE)
+            .chain(
+                self.profiles
+                    .keys()
+                    .map(String::as_str)
+                    .filter(|name| *name != DEFAULT_PROFILE),
+            )
+            .map(Into::into)
+            .collect()
+    }
+
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        if name == DEFAULT_PROFILE {
+            return Some(Profile {
+                name: name.into(),
+                admin: self.admin.clone(),
+                tls: self.tls.clone(),
+            });
+        }
+        self.profiles.get(name).map(|profile| Profile {
+            name: name.into(),
+            admin: profile.admin.clone(),
+            tls: profile.tls.clone().unwrap_or_else(|| self.tls.clone()),
+        })
+    }
+
+    /// Profile to connect to on startup
+    pub fn startup_profile(&self) -> Profile {
+        self.profile(self.default_profile_name())
+            .or_else(|| self.profile(DEFAULT_PROFILE))
+            .expect("default profile always exists")
+    }
+
+    /// Admin and TLS sections of the startup profile, for command line overrides
+    pub fn startup_connection_mut(&mut self) -> (&mut AdminConfig, &mut TlsSettings) {
+        let name = self.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        match self.profiles.get_mut(name) {
+            Some(profile) if name != DEFAULT_PROFILE => (
+                &mut profile.admin,
+                profile.tls.get_or_insert_with(|| self.tls.clone()),
+            ),
+            _ => (&mut self.admin, &mut self.tls),
+        }
+    }
+
+    /// Replaces the admin and TLS settings of the profile with the same name
+    pub fn set_profile(&mut self, profile: Profile) {
+        if profile.name == DEFAULT_PROFILE {
+            self.admin = profile.admin;
+            self.tls = profile.tls;
+            return;
+        }
+        let tls = (profile.tls != self.tls).then_some(profile.tls);
+        self.profiles.insert(
+            profile.name,
+            ProfileConfig {
+                admin: profile.admin,
+                tls,
+            },
+        );
+    }
+
+    /// Configured capabilities file, or the first existing of the default and legacy ones
+    pub fn capabilities_path(&self) -> PathBuf {
+        self.files.capabilities.clone().unwrap_or_else(|| {
+            [VM_CAPABILITIES_FILE, LEGACY_WIREGUARD_VMS_FILE]
+                .into_iter()
+                .map(PathBuf::from)
+                .find(|path| path.exists())
+                .unwrap_or_else(|| PathBuf::from(VM_CAPABILITIES_FILE))
+        })
+    }
+
+    /// The user layer as written, empty if there is none yet
+    pub fn read_user_layer() -> Result<toml::Table, ConfigError> {
+        Ok(read_layer(&Self::user_config_path())?.unwrap_or_default())
+    }
+
+    /// Updates the user layer in place, keeping keys which are not ours
+    pub fn update_user_layer(f: impl FnOnce(&mut toml::Table)) -> Result<(), ConfigError> {
+        let path = Self::user_config_path();
+        let mut table = Self::read_user_layer()?;
+        f(&mut table);
+
+        let write_error = |source| ConfigError::Write {
+            path: path.clone(),
+            source,
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        std::fs::write(&path, toml::to_string(&table)?).map_err(write_error)?;
+        debug!(
+            "User configuration written to {path}",
+            path = path.display()
+        );
+        Ok(())
+    }
+
+    /// Persists the VMs with muted notifications in the user layer
+    pub fn save_muted_vms(&self) -> Result<(), ConfigError> {
+        let muted_vms: toml::value::Array = self
+            .notifications
+            .muted_vms
+            .iter()
+            .map(|vm| vm.clone().into())
+            .collect();
+        Self::update_user_layer(|table| {
+            section(table, "notifications").insert("muted-vms".into(), muted_vms.into());
+        })
+    }
+
+    /// Persists the hour format in the user layer
+    pub fn save_clock_format(&self) -> Result<(), ConfigError> {
+        let format = self.clock.format.as_str();
+        Self::update_user_layer(|table| {
+            section(table, "clock").insert("format".into(), format.into());
+        })
+    }
+
+    /// Persists the night light schedule in the user layer
+    pub fn save_night_light(&self) -> Result<(), ConfigError> {
+        let night_light = &self.display.night_light;
+        Self::update_user_layer(|table| {
+            let table = section(section(table, "display"), "night-light");
+            table.insert("enabled".into(), night_light.enabled.into());
+            for (key, time) in [("start", night_light.start), ("end", night_light.end)] {
+                table.insert(key.into(), time.format("%H:%M").to_string().into());
+            }
+            table.insert(
+                "temperature".into(),
+                i64::from(night_light.temperature).into(),
+            );
+        })
+    }
+
+    /// Persists the admin service address and TLS settings of a profile in the user layer
+    pub fn save_profile(&self, profile: &Profile) -> Result<(), ConfigError> {
+        let is_default = profile.name == DEFAULT_PROFILE;
+        Self::update_user_layer(|table| {
+            let table = if is_default {
+                table
+            } else {
+                section(section(table, "profiles"), &profile.name)
+            };
+            // The endpoint overrides addr and port of lower layers
+            let admin = section(table, "admin");
+            admin.insert(
+                "endpoint".into(),
+                profile.admin.primary().to_string().into(),
+            );
+
+            // Profiles sharing the top-level TLS settings keep following them
+            if !is_default && profile.tls == self.tls {
+                table.remove("tls");
+                return;
+            }
+            let tls = section(table, "tls");
+            tls.insert("enable".into(), profile.tls.enable.into());
+            tls.insert("name".into(), profile.tls.name.clone().into());
+            for (key, path) in [
+                ("ca-cert", &profile.tls.ca_cert),
+                ("cert", &profile.tls.cert),
+                ("key", &profile.tls.key),
+            ] {
+                tls.insert(key.into(), path.to_string_lossy().into_owned().into());
+            }
+        })
+    }
+}
+
+/// Returns the `[name]` table, replacing any non-table value of that key
+pub fn section<'a>(table: &'a mut toml::Table, name: &str) -> &'a mut toml::Table {
+    let value = table
+        .entry(name)
+        .or_insert_with(|| toml::Table::new().into());
+    if !value.is_table() {
+        *value = toml::Table::new().into();
+    }
+    value.as_table_mut().expect("section is a table")
+}
+
+fn read_layer(path: &Path) -> Result<Option<toml::Table>, ConfigError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(ConfigError::Read {
+                path: path.to_owned(),
+                source,
+            });
+        }
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+}
+
+fn merge(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match value {
+            toml::Value::Table(layer) if base.get(&key).is_some_and(toml::Value::is_table) => {
+                if let Some(toml::Value::Table(base)) = base.get_mut(&key) {
+                    merge(base, layer);
+                }
+            }
+            value => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -1,16 +1,44 @@
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use crate::certificate::{self, CertificateInfo};
+use crate::config::TlsSettings;
+use crate::endpoint::{Endpoint, EndpointError};
+
+#[derive(Debug, Clone, Copy)]
+enum CertFile {
+    Ca,
+    Cert,
+    Key,
+}
+
+impl CertFile {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Ca => "Select CA certificate",
+            Self::Cert => "Select certificate",
+            Self::Key => "Select private key",
+        }
+    }
+}
 
 mod imp {
     use glib::Binding;
     use glib::subclass::Signal;
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
-    use gtk::{Button, CompositeTemplate, Entry, glib};
+    use gtk::{Button, CompositeTemplate, Entry, Label, Switch, glib};
     use std::cell::RefCell;
+    use std::path::PathBuf;
     use std::sync::OnceLock;
 
+    use super::CertFile;
+    use crate::config::TlsSettings;
+    use crate::endpoint::Endpoint;
+
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/connection_config.ui")]
     pub struct ConnectionConfig {
@@ -19,10 +47,32 @@ mod imp {
         #[template_child]
         pub port_entry: TemplateChild<Entry>,
         #[template_child]
+        pub tls_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub tls_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub tls_name_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub ca_cert_label: TemplateChild<Label>,
+        #[template_child]
+        pub ca_cert_info_label: TemplateChild<Label>,
+        #[template_child]
+        pub cert_label: TemplateChild<Label>,
+        #[template_child]
+        pub key_label: TemplateChild<Label>,
+        #[template_child]
+        pub cert_info_label: TemplateChild<Label>,
+        #[template_child]
+        pub error_label: TemplateChild<Label>,
+        #[template_child]
         pub apply_button: TemplateChild<Button>,
         #[template_child]
         pub cancel_button: TemplateChild<Button>,
 
+        pub ca_cert: RefCell<PathBuf>,
+        pub cert: RefCell<PathBuf>,
+        pub key: RefCell<PathBuf>,
+
         // Vector holding the bindings to properties of `Object`
         pub bindings: RefCell<Vec<Binding>>,
     }
@@ -47,16 +97,58 @@ mod imp {
     impl ConnectionConfig {
         #[template_callback]
         fn on_apply_clicked(&self) {
-            let (addr, port) = self.obj().get_config();
+            let endpoint = match self.obj().get_endpoint() {
+                Ok(endpoint) => endpoint,
+                Err(e) => {
+                    self.show_error(&format!("Invalid endpoint: {e}"));
+                    return;
+                }
+            };
+            let tls = self.obj().get_tls_settings();
+            if let Err(message) = super::validate(&tls) {
+                self.show_error(&message);
+                return;
+            }
             self.obj()
-                .emit_by_name::<()>("new-config-applied", &[&addr, &port]);
+                .emit_by_name::<()>("new-config-applied", &[&endpoint, &tls]);
+        }
+        #[template_callback]
+        fn on_address_changed(&self) {
+            // A URI carries its own port
+            let is_uri = self.address_entry.text().contains("://");
+            self.port_entry.set_sensitive(!is_uri);
+            self.error_label.set_visible(false);
         }
         #[template_callback]
         fn on_cancel_clicked(&self) {
             self.obj().close();
         }
+        #[template_callback]
+        fn on_tls_toggled(&self) {
+            self.tls_box.set_sensitive(self.tls_switch.is_active());
+            self.error_label.set_visible(false);
+        }
+        #[template_callback]
+        fn on_choose_ca_cert_clicked(&self) {
+            self.obj().choose_file(CertFile::Ca);
+        }
+        #[template_callback]
+        fn on_choose_cert_clicked(&self) {
+            self.obj().choose_file(CertFile::Cert);
+        }
+        #[template_callback]
+        fn on_choose_key_clicked(&self) {
+            self.obj().choose_file(CertFile::Key);
+        }
     } //end #[gtk::template_callbacks]
 
+    impl ConnectionConfig {
+        fn show_error(&self, message: &str) {
+            self.error_label.set_label(message);
+            self.error_label.set_visible(true);
+        }
+    }
+
     impl ObjectImpl for ConnectionConfig {
         fn constructed(&self) {
             // Call "constructed" on parent
@@ -68,7 +160,7 @@ mod imp {
             SIGNALS.get_or_init(|| {
                 vec![
                     Signal::builder("new-config-applied")
-                        .param_types([String::static_type(), u32::static_type()])
+                        .param_types([Endpoint::static_type(), TlsSettings::static_type()])
                         .build(),
                 ]
             })
@@ -88,27 +180,164 @@ pub struct ConnectionConfig(ObjectSubclass<imp::ConnectionConfig>)
 
 impl Default for ConnectionConfig {
     fn default() -> Self {
-        Self::new("", 1)
+        let endpoint = Endpoint::Tcp {
+            addr: String::new(),
+            port: 1,
+        };
+        Self::new(&endpoint, &TlsSettings::default())
     }
 }
 
 impl ConnectionConfig {
-    pub fn new(address: &str, port: u16) -> Self {
+    pub fn new(endpoint: &Endpoint, tls: &TlsSettings) -> Self {
         let config_widget: Self = glib::Object::builder().build();
-        config_widget.imp().address_entry.set_text(address);
-        config_widget
-            .imp()
-            .port_entry
-            .set_text(port.to_string().as_str());
+        let imp = config_widget.imp();
+        if let Endpoint::Tcp { addr, port } = endpoint {
+            imp.address_entry.set_text(addr);
+            imp.port_entry.set_text(port.to_string().as_str());
+        } else {
+            imp.address_entry.set_text(&endpoint.to_string());
+        }
+
+        imp.tls_switch.set_active(tls.enable);
+        imp.tls_box.set_sensitive(tls.enable);
+        imp.tls_name_entry.set_text(&tls.name);
+        imp.ca_cert.replace(tls.ca_cert.clone());
+        imp.cert.replace(tls.cert.clone());
+        imp.key.replace(tls.key.clone());
+        config_widget.update_certificate_info();
         config_widget
     }
 
-    pub fn get_config(&self) -> (String, u32) {
-        let text = self.imp().port_entry.text().to_string();
-        let port = text.parse::<u16>().unwrap_or(0);
-        (
-            String::from(self.imp().address_entry.buffer().text()),
-            port.into(),
-        )
+    /// Address entry as an endpoint URI, or as a TCP host combined with the port entry
+    pub fn get_endpoint(&self) -> Result<Endpoint, EndpointError> {
+        let address = self.imp().address_entry.text();
+        let address = address.trim();
+        if address.contains("://") {
+            address.parse()
+        } else {
+            format!(
+                "tcp://{address}:{port}",
+                port = self.imp().port_entry.text().trim()
+            )
+            .parse()
+        }
+    }
+
+    pub fn get_tls_settings(&self) -> TlsSettings {
+        let imp = self.imp();
+        TlsSettings {
+            enable: imp.tls_switch.is_active(),
+            name: imp.tls_name_entry.text().trim().to_owned(),
+            ca_cert: imp.ca_cert.borrow().clone(),
+            cert: imp.cert.borrow().clone(),
+            key: imp.key.borrow().clone(),
+        }
+    }
+
+    fn cert_path(&self, file: CertFile) -> &RefCell<PathBuf> {
+        match file {
+            CertFile::Ca => &self.imp().ca_cert,
+            CertFile::Cert => &self.imp().cert,
+            CertFile::Key => &self.imp().key,
+        }
+    }
+
+    fn choose_file(&self, file: CertFile) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("PEM files"));
+        for pattern in ["*.pem", "*.crt", "*.key"] {
+            filter.add_pattern(pattern);
+        }
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(file.title())
+            .modal(true)
+            .filters(&filters)
+            .default_filter(&filter)
+            .build();
+        let current = self.cert_path(file).borrow().clone();
+        if current.exists() {
+            dialog.set_initial_file(Some(&gio::File::for_path(&current)));
+        }
+
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = config)]
+            self,
+            async move {
+                let Ok(chosen) = dialog.open_future(Some(&config)).await else {
+                    return;
+                };
+                let Some(path) = chosen.path() else {
+                    return;
+                };
+                config.cert_path(file).replace(path);
+                config.update_certificate_info();
+            }
+        ));
+    }
+
+    fn update_certificate_info(&self) {
+        let imp = self.imp();
+        let tls = self.get_tls_settings();
+        imp.error_label.set_visible(false);
+        imp.ca_cert_label.set_label(&path_label(&tls.ca_cert));
+        imp.cert_label.set_label(&path_label(&tls.cert));
+        imp.key_label.set_label(&path_label(&tls.key));
+
+        set_info(
+            &imp.ca_cert_info_label,
+            certificate::inspect_ca(&tls.ca_cert),
+        );
+        set_info(
+            &imp.cert_info_label,
+            certificate::inspect_cert_and_key(&tls.cert, &tls.key),
+        );
+    }
+}
+
+fn validate(tls: &TlsSettings) -> Result<(), String> {
+    if !tls.enable {
+        return Ok(());
+    }
+    if tls.name.is_empty() {
+        return Err("TLS server name is required".into());
+    }
+    let ca = certificate::inspect_ca(&tls.ca_cert)
+        .map_err(|e| format!("Invalid CA certificate: {e}"))?;
+    let cert = certificate::inspect_cert_and_key(&tls.cert, &tls.key)
+        .map_err(|e| format!("Invalid certificate or key: {e}"))?;
+    for (what, info) in [("CA certificate", ca), ("Certificate", cert)] {
+        if info.is_expired() {
+            return Err(format!("{what} has expired: {}", info.summary()));
+        }
+    }
+    Ok(())
+}
+
+fn path_label(path: &Path) -> String {
+    if path.as_os_str().is_empty() {
+        "Not set".into()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn set_info(label: &gtk::Label, info: Result<CertificateInfo, glib::Error>) {
+    match info {
+        Ok(info) => {
+            label.set_label(&info.summary());
+            if info.is_expired() {
+                label.add_css_class("required-text");
+            } else {
+                label.remove_css_class("required-text");
+            }
+        }
+        Err(e) => {
+            label.set_label(&e.to_string());
+            label.add_css_class("required-text");
+        }
     }
 }
@@ -1,12 +1,26 @@
 use gtk::glib;
 
-#[derive(Debug, Clone, Copy, glib::Enum)]
+#[derive(Debug, Clone, Copy, glib::Enum, strum::EnumString)]
 #[enum_type(name = "CtrlControlAction")]
+#[strum(serialize_all = "lowercase")]
 #[repr(u8)]
 pub enum ControlAction {
     Start,
     Restart,
     Pause,
     Resume,
+    #[strum(serialize = "stop", serialize = "shutdown")]
     Shutdown,
 }
+
+impl ControlAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ControlAction::Start => "Start",
+            ControlAction::Restart => "Restart",
+            ControlAction::Pause => "Pause",
+            ControlAction::Resume => "Resume",
+            ControlAction::Shutdown => "Shutdown",
+        }
+    }
+}
@@ -12,6 +12,7 @@ mod imp {
     pub struct DataData {
         pub name: String,
         pub display: String,
+        pub group: String,
     }
 
     #[derive(Default, Properties)]
@@ -19,6 +20,7 @@ mod imp {
     pub struct DataGObject {
         #[property(name = "name", get, set, type = String, member = name)]
         #[property(name = "display", get, set, type = String, member = display)]
+        #[property(name = "group", get, set, type = String, member = group)]
         pub data: RefCell<DataData>,
     }
 
@@ -0,0 +1,202 @@
+use chrono::Local;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::config::ClockFormat;
+use crate::locale_provider::ClockStatus;
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{Box, Calendar, CheckButton, CompositeTemplate, Label, SpinButton, Switch, glib};
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use crate::config::ClockFormat;
+    use crate::locale_provider::ClockStatus;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/date_time_settings_page.ui")]
+    pub struct DateTimeSettingsPage {
+        #[template_child]
+        pub time_label: TemplateChild<Label>,
+        #[template_child]
+        pub date_label: TemplateChild<Label>,
+        #[template_child]
+        pub sync_label: TemplateChild<Label>,
+        #[template_child]
+        pub ntp_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub manual_box: TemplateChild<Box>,
+        #[template_child]
+        pub calendar: TemplateChild<Calendar>,
+        #[template_child]
+        pub hour_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub minute_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub format_24h_button: TemplateChild<CheckButton>,
+        #[template_child]
+        pub format_12h_button: TemplateChild<CheckButton>,
+
+        /// Last status reported by timedated
+        pub(super) status: Cell<ClockStatus>,
+        pub(super) format: Cell<ClockFormat>,
+        pub(super) tick: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DateTimeSettingsPage {
+        const NAME: &'static str = "DateTimeSettingsPage";
+        type Type = super::DateTimeSettingsPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl DateTimeSettingsPage {
+        #[template_callback]
+        fn on_format_toggled(&self) {
+            let format = if self.format_12h_button.is_active() {
+                ClockFormat::Hours12
+            } else {
+                ClockFormat::Hours24
+            };
+            if format == self.format.get() {
+                return;
+            }
+            self.format.set(format);
+            self.obj().refresh_clock();
+            let hours12 = format == ClockFormat::Hours12;
+            self.obj()
+                .emit_by_name::<()>("clock-format-changed", &[&hours12]);
+        }
+    }
+
+    impl ObjectImpl for DateTimeSettingsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            for spin in [&*self.hour_spin, &*self.minute_spin] {
+                spin.connect_output(|spin| {
+                    spin.set_text(&format!("{:02}", spin.value_as_int()));
+                    glib::Propagation::Stop
+                });
+            }
+            self.obj().set_status(ClockStatus::default());
+            self.obj().reset_manual_time();
+            self.obj().refresh_clock();
+            self.tick.replace(Some(glib::timeout_add_seconds_local(
+                1,
+                glib::clone!(
+                    #[weak(rename_to = page)]
+                    self.obj(),
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        page.refresh_clock();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            )));
+        }
+
+        fn dispose(&self) {
+            if let Some(tick) = self.tick.take() {
+                tick.remove();
+            }
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    // True for the 12-hour format
+                    Signal::builder("clock-format-changed")
+                        .param_types([bool::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for DateTimeSettingsPage {}
+    impl BoxImpl for DateTimeSettingsPage {}
+}
+
+glib::wrapper! {
+pub struct DateTimeSettingsPage(ObjectSubclass<imp::DateTimeSettingsPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for DateTimeSettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DateTimeSettingsPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Shows the GUI VM clock, the controls stay disabled as the admin service has no clock
+    /// setting
+    pub fn set_status(&self, status: ClockStatus) {
+        let imp = self.imp();
+        let was_ntp = imp.status.replace(status).ntp;
+        imp.ntp_switch.set_active(status.ntp);
+        imp.sync_label
+            .set_label(match (status.ntp, status.synchronized) {
+                (true, true) => "Synchronized with a time server",
+                (true, false) => "Waiting for a time server",
+                (false, _) => "Set manually",
+            });
+        // The manual section shows the clock NTP was keeping
+        if was_ntp && !status.ntp {
+            self.reset_manual_time();
+        }
+    }
+
+    pub fn set_clock_format(&self, format: ClockFormat) {
+        let imp = self.imp();
+        imp.format.set(format);
+        match format {
+            ClockFormat::Hours24 => imp.format_24h_button.set_active(true),
+            ClockFormat::Hours12 => imp.format_12h_button.set_active(true),
+        }
+        self.refresh_clock();
+    }
+
+    fn refresh_clock(&self) {
+        let imp = self.imp();
+        let now = Local::now();
+        imp.time_label.set_label(
+            &now.format(match imp.format.get() {
+                ClockFormat::Hours24 => "%H:%M:%S",
+                ClockFormat::Hours12 => "%I:%M:%S %p",
+            })
+            .to_string(),
+        );
+        imp.date_label
+            .set_label(&now.format("%A, %-d %B %Y").to_string());
+    }
+
+    fn reset_manual_time(&self) {
+        let imp = self.imp();
+        if let Ok(now) = glib::DateTime::now_local() {
+            imp.calendar.select_day(&now);
+            imp.hour_spin.set_value(now.hour().into());
+            imp.minute_spin.set_value(now.minute().into());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use async_channel::{Receiver, Sender};
+use zbus::object_server::SignalEmitter;
+use zbus::{Connection, fdo, interface};
+
+use crate::control_action::ControlAction;
+use crate::window::Page;
+
+pub const BUS_NAME: &str = "ae.tii.ghaf.ControlPanel";
+pub const OBJECT_PATH: &str = "/ae/tii/ghaf/ControlPanel";
+
+/// Method call forwarded to the GTK main loop, zbus runs the interface on its own executor
+#[derive(Debug)]
+pub enum Request {
+    ShowPage(Page),
+    SelectUnit(String),
+    Control(ControlAction, String),
+}
+
+pub type Reply = Sender<Result<(), String>>;
+
+struct ControlPanelInterface {
+    requests: Sender<(Request, Reply)>,
+}
+
+impl ControlPanelInterface {
+    async fn call(&self, request: Request) -> fdo::Result<()> {
+        let (reply_tx, reply_rx) = async_channel::bounded(1);
+        self.requests
+            .send((request, reply_tx))
+            .await
+            .map_err(|_| fdo::Error::Failed("Control panel is shutting down".into()))?;
+        reply_rx
+            .recv()
+            .await
+            .map_err(|_| fdo::Error::Failed("No reply from the control panel".into()))?
+            .map_err(fdo::Error::Failed)
+    }
+}
+
+#[interface(name = "ae.tii.ghaf.ControlPanel")]
+impl ControlPanelInterface {
+    /// Presents the window on `services`, `launcher`, `activity`, `settings`, `locale`,
+    /// `datetime`, `display`, `audio`, `network`, `power`, `updates` or `about`
+    async fn show_page(&self, page: &str) -> fdo::Result<()> {
+        let page = page
+            .parse()
+            .map_err(|_| fdo::Error::InvalidArgs(format!("Unknown page {page}")))?;
+        self.call(Request::ShowPage(page)).await
+    }
+
+    /// Presents the window on the services page with `unit` selected
+    async fn select_unit(&self, unit: &str) -> fdo::Result<()> {
+        self.call(Request::SelectUnit(unit.into())).await
+    }
+
+    /// Applies `start`, `stop`, `pause`, `resume` or `restart` to `unit`
+    async fn control_unit(&self, unit: &str, action: &str) -> fdo::Result<()> {
+        let action = action
+            .parse()
+            .map_err(|_| fdo::Error::InvalidArgs(format!("Unknown action {action}")))?;
+        self.call(Request::Control(action, unit.into())).await
+    }
+
+    #[zbus(signal)]
+    async fn unit_status_changed(
+        emitter: &SignalEmitter<'_>,
+        unit: &str,
+        status: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Exported `ae.tii.ghaf.ControlPanel` object
+#[derive(Debug, Clone)]
+pub struct DbusService {
+    connection: Connection,
+}
+
+impl DbusService {
+    /// Claims the bus name on the session bus, method calls arrive on the returned receiver
+    pub async fn start() -> zbus::Result<(Self, Receiver<(Request, Reply)>)> {
+        let (requests, receiver) = async_channel::unbounded();
+        let connection = zbus::connection::Builder::session()?
+            .name(BUS_NAME)?
+            .serve_at(OBJECT_PATH, ControlPanelInterface { requests })?
+            .build()
+            .await?;
+        Ok((Self { connection }, receiver))
+    }
+
+    pub async fn emit_unit_status_changed(&self, unit: &str, status: &str) -> zbus::Result<()> {
+        let emitter = SignalEmitter::new(&self.connection, OBJECT_PATH)?;
+        ControlPanelInterface::unit_status_changed(&emitter, unit, status).await
+    }
+}
@@ -0,0 +1,64 @@
+use gtk::glib;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error as ThisError;
+
+use crate::window::Page;
+
+pub const SCHEME: &str = "ghaf-panel";
+
+/// Characters kept as they are in unit names, e.g. `microvm@chrome-vm.service`
+const UNIT_RESERVED_CHARS: &str = "@";
+
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkError {
+    #[error("\"{0}\" is not a {SCHEME}:// link")]
+    UnknownScheme(String),
+    #[error("unknown page \"{0}\"")]
+    UnknownPage(String),
+    #[error("invalid unit name \"{0}\"")]
+    InvalidUnit(String),
+}
+
+/// Location in the window, written as `ghaf-panel://<page>` such as
+/// `ghaf-panel://settings/about`, or `ghaf-panel://services/<unit>` to select a unit
+#[derive(Debug, Clone)]
+pub enum DeepLink {
+    Page(Page),
+    Unit(String),
+}
+
+impl FromStr for DeepLink {
+    type Err = DeepLinkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = s
+            .strip_prefix(SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(|| DeepLinkError::UnknownScheme(s.into()))?
+            .trim_end_matches('/');
+        if let Ok(page) = path.parse() {
+            return Ok(Self::Page(page));
+        }
+        let unit = path
+            .strip_prefix("services/")
+            .ok_or_else(|| DeepLinkError::UnknownPage(path.into()))?;
+        glib::Uri::unescape_string(unit, Some("/"))
+            .filter(|unit| !unit.is_empty())
+            .map(|unit| Self::Unit(unit.into()))
+            .ok_or_else(|| DeepLinkError::InvalidUnit(unit.into()))
+    }
+}
+
+impl fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Page(page) => write!(f, "{SCHEME}://{page}"),
+            Self::Unit(unit) => write!(
+                f,
+                "{SCHEME}://services/{unit}",
+                unit = glib::Uri::escape_string(unit, Some(UNIT_RESERVED_CHARS), true)
+            ),
+        }
+    }
+}
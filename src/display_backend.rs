@@ -0,0 +1,402 @@
+use futures::future::LocalBoxFuture;
+use gtk::gio;
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use thiserror::Error as ThisError;
+
+use crate::config::NightLightConfig;
+use crate::prelude::*;
+use crate::session;
+
+const WLR_RANDR: &str = "wlr-randr";
+const WLSUNSET: &str = "wlsunset";
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+/// Colour temperature outside the night light schedule
+const DAY_TEMPERATURE: u32 = 6500;
+
+/// Resolution and refresh rate of an output
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Mode {
+    pub width: u32,
+    pub height: u32,
+    /// In Hz
+    pub refresh: f64,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}×{} @ {:.2} Hz", self.width, self.height, self.refresh)
+    }
+}
+
+/// Enabled output of the compositor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    /// Connector, e.g. `eDP-1`
+    pub name: String,
+    pub description: String,
+    pub modes: Vec<Mode>,
+    pub current_mode: Option<Mode>,
+    pub scale: f64,
+}
+
+#[derive(ThisError, Debug)]
+pub enum DisplayError {
+    #[error("cannot run {command}: {source}")]
+    Spawn {
+        command: &'static str,
+        source: std::io::Error,
+    },
+    #[error("{command} failed: {stderr}")]
+    Command {
+        command: &'static str,
+        stderr: String,
+    },
+    #[error("unexpected {WLR_RANDR} output: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("cannot read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("unknown output {0}")]
+    UnknownOutput(String),
+    #[error("{output} does not support {mode}")]
+    UnsupportedMode { output: String, mode: Mode },
+    #[error("no backlight to adjust")]
+    NoBacklight,
+    #[error(transparent)]
+    Session(#[from] zbus::Error),
+    #[error("display task was interrupted")]
+    Interrupted,
+}
+
+/// Screen settings of the compositor and backlight, or an in-memory stand-in for testing
+pub trait DisplayBackend: fmt::Debug {
+    fn outputs(&self) -> LocalBoxFuture<'_, Result<Vec<Output>, DisplayError>>;
+
+    /// Applies `mode`, one of the output modes, and `scale`
+    fn configure_output<'a>(
+        &'a self,
+        name: &'a str,
+        mode: &'a Mode,
+        scale: f64,
+    ) -> LocalBoxFuture<'a, Result<(), DisplayError>>;
+
+    /// Backlight level from 0 to 1, `None` without a backlight
+    fn brightness(&self) -> LocalBoxFuture<'_, Result<Option<f64>, DisplayError>>;
+
+    fn set_brightness(&self, brightness: f64) -> LocalBoxFuture<'_, Result<(), DisplayError>>;
+
+    /// Starts, reschedules or stops the night light
+    fn set_night_light(&self, config: &NightLightConfig) -> Result<(), DisplayError>;
+
+    /// Stops the night light when the panel exits, leaving the saved schedule alone
+    fn stop_night_light(&self);
+}
+
+#[derive(Deserialize)]
+struct RandrMode {
+    #[serde(flatten)]
+    mode: Mode,
+    #[serde(default)]
+    current: bool,
+}
+
+#[derive(Deserialize)]
+struct RandrOutput {
+    name: String,
+    #[serde(default)]
+    description: String,
+    enabled: bool,
+    modes: Vec<RandrMode>,
+    #[serde(default)]
+    scale: Option<f64>,
+}
+
+impl From<RandrOutput> for Output {
+    fn from(output: RandrOutput) -> Self {
+        Self {
+            current_mode: output
+                .modes
+                .iter()
+                .find(|mode| mode.current)
+                .map(|mode| mode.mode),
+            modes: output.modes.into_iter().map(|mode| mode.mode).collect(),
+            name: output.name,
+            description: output.description,
+            scale: output.scale.unwrap_or(1.),
+        }
+    }
+}
+
+/// Outputs configured through `wlr-randr`, the backlight through logind and the night light
+/// by a `wlsunset` process owned by the panel, so it stops with it
+#[derive(Debug, Default)]
+pub struct SystemDisplayBackend {
+    night_light: RefCell<Option<Child>>,
+}
+
+impl SystemDisplayBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn wlr_randr(args: Vec<String>) -> Result<String, DisplayError> {
+        debug!("Running {WLR_RANDR} {}", args.join(" "));
+        let output = gio::spawn_blocking(move || Command::new(WLR_RANDR).args(args).output())
+            .await
+            .map_err(|_| DisplayError::Interrupted)?
+            .map_err(|source| DisplayError::Spawn {
+                command: WLR_RANDR,
+                source,
+            })?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(DisplayError::Command {
+                command: WLR_RANDR,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            })
+        }
+    }
+
+    /// First device of the backlight class, with its current and maximum levels
+    fn backlight() -> Result<Option<(String, u32, u32)>, DisplayError> {
+        let dir = Path::new(BACKLIGHT_DIR);
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(None);
+        };
+        let Some(name) = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .min()
+        else {
+            return Ok(None);
+        };
+        let read = |file: &str| -> Result<u32, DisplayError> {
+            let path = dir.join(&name).join(file);
+            let content = fs::read_to_string(&path).map_err(|source| DisplayError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            content.trim().parse().map_err(|_| DisplayError::Read {
+                path,
+                source: std::io::ErrorKind::InvalidData.into(),
+            })
+        };
+        let (brightness, max) = (read("brightness")?, read("max_brightness")?);
+        Ok(Some((name, brightness, max)))
+    }
+}
+
+impl DisplayBackend for SystemDisplayBackend {
+    fn outputs(&self) -> LocalBoxFuture<'_, Result<Vec<Output>, DisplayError>> {
+        Box::pin(async {
+            let json = Self::wlr_randr(vec!["--json".into()]).await?;
+            let outputs: Vec<RandrOutput> = serde_json::from_str(&json)?;
+            Ok(outputs
+                .into_iter()
+                .filter(|output| output.enabled)
+                .map(Output::from)
+                .collect())
+        })
+    }
+
+    fn configure_output<'a>(
+        &'a self,
+        name: &'a str,
+        mode: &'a Mode,
+        scale: f64,
+    ) -> LocalBoxFuture<'a, Result<(), DisplayError>> {
+        Box::pin(async move {
+            Self::wlr_randr(vec![
+                "--output".into(),
+                name.into(),
+                "--mode".into(),
+                format!("{}x{}@{:.3}Hz", mode.width, mode.height, mode.refresh),
+                "--scale".into(),
+                format!("{scale:.2}"),
+            ])
+            .await
+            .map(drop)
+        })
+    }
+
+    fn brightness(&self) -> LocalBoxFuture<'_, Result<Option<f64>, DisplayError>> {
+        Box::pin(async {
+            Ok(Self::backlight()?
+                .filter(|(_, _, max)| *max > 0)
+                .map(|(_, brightness, max)| f64::from(brightness) / f64::from(max)))
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn set_brightness(&self, brightness: f64) -> LocalBoxFuture<'_, Result<(), DisplayError>> {
+        Box::pin(async move {
+            let (name, _, max) = Self::backlight()?.ok_or(DisplayError::NoBacklight)?;
+            // Level 0 turns some panels off, leaving no way to see the slider
+            let level = (brightness.clamp(0., 1.) * f64::from(max)).round() as u32;
+            session::set_brightness("backlight", &name, level.max(1)).await?;
+            Ok(())
+        })
+    }
+
+    fn set_night_light(&self, config: &NightLightConfig) -> Result<(), DisplayError> {
+        self.stop_night_light();
+        if !config.enabled {
+            return Ok(());
+        }
+        let mut command = Command::new(WLSUNSET);
+        command
+            .arg("-t")
+            .arg(config.temperature.to_string())
+            .arg("-T")
+            .arg(
+                DAY_TEMPERATURE
+                    .max(config.temperature.saturating_add(1))
+                    .to_string(),
+            )
+            .arg("-s")
+            .arg(config.start.format("%H:%M").to_string())
+            .arg("-S")
+            .arg(config.end.format("%H:%M").to_string());
+        // Also gone when the panel is killed and its Drop and shutdown never run
+        // SAFETY: prctl is async-signal-safe and touches no memory of the parent
+        unsafe {
+            command.pre_exec(|| {
+                if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = command.spawn().map_err(|source| DisplayError::Spawn {
+            command: WLSUNSET,
+            source,
+        })?;
+        self.night_light.replace(Some(child));
+        Ok(())
+    }
+
+    fn stop_night_light(&self) {
+        if let Some(mut child) = self.night_light.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for SystemDisplayBackend {
+    fn drop(&mut self) {
+        self.stop_night_light();
+    }
+}
+
+/// Two outputs and a backlight kept in memory, for trying the Display page without the hardware
+#[derive(Debug)]
+pub struct FakeDisplayBackend {
+    outputs: RefCell<Vec<Output>>,
+    brightness: Cell<f64>,
+}
+
+impl FakeDisplayBackend {
+    pub fn new() -> Self {
+        let mode = |width, height, refresh| Mode {
+            width,
+            height,
+            refresh,
+        };
+        let laptop_modes = vec![
+            mode(2560, 1600, 60.),
+            mode(1920, 1200, 60.),
+            mode(1280, 800, 60.),
+        ];
+        let external_modes = vec![
+            mode(3840, 2160, 60.),
+            mode(3840, 2160, 30.),
+            mode(1920, 1080, 60.),
+        ];
+        Self {
+            outputs: RefCell::new(vec![
+                Output {
+                    name: "FAKE-1".into(),
+                    description: "Built-in panel".into(),
+                    current_mode: laptop_modes.first().copied(),
+                    modes: laptop_modes,
+                    scale: 1.5,
+                },
+                Output {
+                    name: "FAKE-2".into(),
+                    description: "External monitor".into(),
+                    current_mode: external_modes.first().copied(),
+                    modes: external_modes,
+                    scale: 2.,
+                },
+            ]),
+            brightness: Cell::new(0.8),
+        }
+    }
+}
+
+impl Default for FakeDisplayBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayBackend for FakeDisplayBackend {
+    fn outputs(&self) -> LocalBoxFuture<'_, Result<Vec<Output>, DisplayError>> {
+        Box::pin(async { Ok(self.outputs.borrow().clone()) })
+    }
+
+    fn configure_output<'a>(
+        &'a self,
+        name: &'a str,
+        mode: &'a Mode,
+        scale: f64,
+    ) -> LocalBoxFuture<'a, Result<(), DisplayError>> {
+        Box::pin(async move {
+            let mut outputs = self.outputs.borrow_mut();
+            let output = outputs
+                .iter_mut()
+                .find(|output| output.name == name)
+                .ok_or_else(|| DisplayError::UnknownOutput(name.into()))?;
+            if !output.modes.contains(mode) {
+                return Err(DisplayError::UnsupportedMode {
+                    output: name.into(),
+                    mode: *mode,
+                });
+            }
+            debug!("Fake output {name} set to {mode} at scale {scale}");
+            output.current_mode = Some(*mode);
+            output.scale = scale;
+            Ok(())
+        })
+    }
+
+    fn brightness(&self) -> LocalBoxFuture<'_, Result<Option<f64>, DisplayError>> {
+        Box::pin(async { Ok(Some(self.brightness.get())) })
+    }
+
+    fn set_brightness(&self, brightness: f64) -> LocalBoxFuture<'_, Result<(), DisplayError>> {
+        Box::pin(async move {
+            self.brightness.set(brightness.clamp(0., 1.));
+            Ok(())
+        })
+    }
+
+    fn set_night_light(&self, config: &NightLightConfig) -> Result<(), DisplayError> {
+        debug!("Fake night light set to {config:?}");
+        Ok(())
+    }
+
+    fn stop_night_light(&self) {
+        debug!("Fake night light stopped");
+    }
+}
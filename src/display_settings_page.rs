@@ -0,0 +1,313 @@
+use chrono::{NaiveTime, Timelike};
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::config::NightLightConfig;
+use crate::display_backend::Output;
+
+/// Offered output scales, the current one is matched to the nearest
+const SCALES: [f64; 7] = [1., 1.25, 1.5, 1.75, 2., 2.5, 3.];
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{
+        Box, CompositeTemplate, DropDown, Grid, Label, Scale, SpinButton, StringList, Switch, glib,
+    };
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use crate::config::NightLightConfig;
+    use crate::display_backend::Output;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/display_settings_page.ui")]
+    pub struct DisplaySettingsPage {
+        #[template_child]
+        pub output_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub output_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub mode_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub scale_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub no_output_label: TemplateChild<Label>,
+        #[template_child]
+        pub brightness_box: TemplateChild<Box>,
+        #[template_child]
+        pub brightness_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub night_light_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub night_light_box: TemplateChild<Box>,
+        #[template_child]
+        pub start_hour_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub start_minute_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub end_hour_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub end_minute_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub temperature_scale: TemplateChild<Scale>,
+
+        pub(super) outputs: RefCell<Vec<Output>>,
+        /// Last level reported by the backend, the slider only emits when it differs
+        pub(super) brightness: Cell<f64>,
+        pub(super) night_light: RefCell<NightLightConfig>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DisplaySettingsPage {
+        const NAME: &'static str = "DisplaySettingsPage";
+        type Type = super::DisplaySettingsPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl DisplaySettingsPage {
+        #[template_callback]
+        #[allow(clippy::cast_possible_truncation)]
+        pub(super) fn on_output_selected(&self) {
+            let outputs = self.outputs.borrow();
+            let Some(output) = outputs.get(self.output_switch.selected() as usize) else {
+                return;
+            };
+            let modes: Vec<String> = output.modes.iter().map(ToString::to_string).collect();
+            let modes: Vec<&str> = modes.iter().map(String::as_str).collect();
+            self.mode_switch.set_model(Some(&StringList::new(&modes)));
+            if let Some(index) = output
+                .current_mode
+                .and_then(|current| output.modes.iter().position(|mode| *mode == current))
+            {
+                self.mode_switch.set_selected(index as u32);
+            }
+            self.scale_switch
+                .set_selected(super::nearest_scale(output.scale));
+        }
+
+        #[template_callback]
+        fn on_apply_output_clicked(&self) {
+            let outputs = self.outputs.borrow();
+            let Some(output) = outputs.get(self.output_switch.selected() as usize) else {
+                return;
+            };
+            let Some(mode) = output.modes.get(self.mode_switch.selected() as usize) else {
+                return;
+            };
+            let scale = super::SCALES
+                .get(self.scale_switch.selected() as usize)
+                .copied()
+                .unwrap_or(1.);
+            self.obj().emit_by_name::<()>(
+                "output-changed",
+                &[
+                    &output.name,
+                    &mode.width,
+                    &mode.height,
+                    &mode.refresh,
+                    &scale,
+                ],
+            );
+        }
+
+        #[template_callback]
+        fn on_brightness_changed(&self) {
+            let brightness = self.brightness_scale.value();
+            if (brightness - self.brightness.get()).abs() < 0.005 {
+                return;
+            }
+            self.brightness.set(brightness);
+            self.obj()
+                .emit_by_name::<()>("brightness-changed", &[&brightness]);
+        }
+
+        #[template_callback]
+        fn on_night_light_toggled(&self) {
+            let enabled = self.night_light_switch.is_active();
+            self.night_light_box.set_sensitive(enabled);
+            if enabled == self.night_light.borrow().enabled {
+                return;
+            }
+            self.obj().emit_night_light();
+        }
+
+        #[template_callback]
+        fn on_apply_night_light_clicked(&self) {
+            self.obj().emit_night_light();
+        }
+    }
+
+    impl ObjectImpl for DisplaySettingsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            for spin in [
+                &*self.start_hour_spin,
+                &*self.start_minute_spin,
+                &*self.end_hour_spin,
+                &*self.end_minute_spin,
+            ] {
+                spin.connect_output(|spin| {
+                    spin.set_text(&format!("{:02}", spin.value_as_int()));
+                    glib::Propagation::Stop
+                });
+            }
+            let scales: Vec<String> = super::SCALES
+                .iter()
+                .map(|scale| format!("{:.0} %", scale * 100.))
+                .collect();
+            let scales: Vec<&str> = scales.iter().map(String::as_str).collect();
+            self.scale_switch.set_model(Some(&StringList::new(&scales)));
+            self.obj().set_outputs(Vec::new());
+            self.obj().set_night_light(&NightLightConfig::default());
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    // Output name, mode width, height and refresh rate, scale
+                    Signal::builder("output-changed")
+                        .param_types([
+                            String::static_type(),
+                            u32::static_type(),
+                            u32::static_type(),
+                            f64::static_type(),
+                            f64::static_type(),
+                        ])
+                        .build(),
+                    // From 0 to 1
+                    Signal::builder("brightness-changed")
+                        .param_types([f64::static_type()])
+                        .build(),
+                    // Enabled, start and end in minutes after midnight, temperature
+                    Signal::builder("night-light-changed")
+                        .param_types([
+                            bool::static_type(),
+                            u32::static_type(),
+                            u32::static_type(),
+                            u32::static_type(),
+                        ])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for DisplaySettingsPage {}
+    impl BoxImpl for DisplaySettingsPage {}
+}
+
+glib::wrapper! {
+pub struct DisplaySettingsPage(ObjectSubclass<imp::DisplaySettingsPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for DisplaySettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn nearest_scale(scale: f64) -> u32 {
+    SCALES
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - scale).abs().total_cmp(&(*b - scale).abs()))
+        .map_or(0, |(index, _)| index as u32)
+}
+
+impl DisplaySettingsPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Keeps the selected output when it is still there
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn set_outputs(&self, outputs: Vec<Output>) {
+        let imp = self.imp();
+        let selected = imp
+            .outputs
+            .borrow()
+            .get(imp.output_switch.selected() as usize)
+            .map(|output| output.name.clone());
+        let labels: Vec<String> = outputs
+            .iter()
+            .map(|output| {
+                if output.description.is_empty() {
+                    output.name.clone()
+                } else {
+                    format!("{} ({})", output.description, output.name)
+                }
+            })
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let index = selected
+            .and_then(|name| outputs.iter().position(|output| output.name == name))
+            .unwrap_or(0);
+        imp.output_grid.set_visible(!outputs.is_empty());
+        imp.no_output_label.set_visible(outputs.is_empty());
+        imp.outputs.replace(outputs);
+        imp.output_switch
+            .set_model(Some(&gtk::StringList::new(&labels)));
+        imp.output_switch.set_selected(index as u32);
+        imp.on_output_selected();
+    }
+
+    /// Hides the slider without a backlight
+    pub fn set_brightness(&self, brightness: Option<f64>) {
+        let imp = self.imp();
+        imp.brightness_box.set_visible(brightness.is_some());
+        if let Some(brightness) = brightness {
+            imp.brightness.set(brightness);
+            imp.brightness_scale.set_value(brightness);
+        }
+    }
+
+    pub fn set_night_light(&self, config: &NightLightConfig) {
+        let imp = self.imp();
+        imp.night_light.replace(config.clone());
+        imp.night_light_switch.set_active(config.enabled);
+        imp.night_light_box.set_sensitive(config.enabled);
+        imp.start_hour_spin.set_value(config.start.hour().into());
+        imp.start_minute_spin
+            .set_value(config.start.minute().into());
+        imp.end_hour_spin.set_value(config.end.hour().into());
+        imp.end_minute_spin.set_value(config.end.minute().into());
+        imp.temperature_scale.set_value(config.temperature.into());
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn emit_night_light(&self) {
+        let imp = self.imp();
+        let minutes = |hour: &gtk::SpinButton, minute: &gtk::SpinButton| {
+            (hour.value_as_int() * 60 + minute.value_as_int()) as u32
+        };
+        let enabled = imp.night_light_switch.is_active();
+        let start = minutes(&imp.start_hour_spin, &imp.start_minute_spin);
+        let end = minutes(&imp.end_hour_spin, &imp.end_minute_spin);
+        let temperature = imp.temperature_scale.value().round() as u32;
+        self.emit_by_name::<()>(
+            "night-light-changed",
+            &[&enabled, &start, &end, &temperature],
+        );
+    }
+}
+
+/// Time of day `minutes` after midnight, as sent by "night-light-changed"
+pub fn time_of_day(minutes: u32) -> Option<NaiveTime> {
+    NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0)
+}
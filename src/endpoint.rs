@@ -0,0 +1,117 @@
+use givc_common::address::EndpointAddress;
+use gtk::glib;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum EndpointError {
+    #[error("unsupported scheme \"{0}\", expected tcp, unix or vsock")]
+    UnknownScheme(String),
+    #[error("\"{0}\" has no port")]
+    MissingPort(String),
+    #[error("invalid port \"{0}\"")]
+    InvalidPort(String),
+    #[error("invalid vsock CID \"{0}\"")]
+    InvalidCid(String),
+    #[error("host must not be empty")]
+    EmptyHost,
+    #[error("unix socket path \"{0}\" must be absolute")]
+    RelativePath(String),
+}
+
+/// Admin service endpoint, written as `tcp://host:port`, `unix:///path/to/socket` or
+/// `vsock://cid:port`. A URI without scheme such as `host:port` is a TCP endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "AdminEndpoint")]
+pub enum Endpoint {
+    Tcp { addr: String, port: u16 },
+    Unix(PathBuf),
+    Vsock { cid: u32, port: u32 },
+}
+
+impl Endpoint {
+    #[cfg_attr(feature = "mock", allow(dead_code))]
+    pub fn to_address(&self) -> EndpointAddress {
+        match self {
+            Self::Tcp { addr, port } => EndpointAddress::Tcp {
+                addr: addr.clone(),
+                port: *port,
+            },
+            Self::Unix(path) => EndpointAddress::Unix(path.to_string_lossy().into_owned()),
+            Self::Vsock { cid, port } => {
+                EndpointAddress::Vsock(tokio_vsock::VsockAddr::new(*cid, *port))
+            }
+        }
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = EndpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (scheme, rest) = s.split_once("://").unwrap_or(("tcp", s));
+        match scheme {
+            "tcp" => {
+                let (addr, port) = rest
+                    .rsplit_once(':')
+                    .ok_or_else(|| EndpointError::MissingPort(s.into()))?;
+                // IPv6 addresses are written in brackets
+                let addr = addr
+                    .strip_prefix('[')
+                    .and_then(|addr| addr.strip_suffix(']'))
+                    .unwrap_or(addr);
+                if addr.is_empty() {
+                    return Err(EndpointError::EmptyHost);
+                }
+                let port = port
+                    .parse()
+                    .ok()
+                    .filter(|port| *port != 0)
+                    .ok_or_else(|| EndpointError::InvalidPort(port.into()))?;
+                Ok(Self::Tcp {
+                    addr: addr.into(),
+                    port,
+                })
+            }
+            "unix" if rest.starts_with('/') => Ok(Self::Unix(rest.into())),
+            "unix" => Err(EndpointError::RelativePath(rest.into())),
+            "vsock" => {
+                let (cid, port) = rest
+                    .split_once(':')
+                    .ok_or_else(|| EndpointError::MissingPort(s.into()))?;
+                Ok(Self::Vsock {
+                    cid: cid
+                        .parse()
+                        .map_err(|_| EndpointError::InvalidCid(cid.into()))?,
+                    port: port
+                        .parse()
+                        .map_err(|_| EndpointError::InvalidPort(port.into()))?,
+                })
+            }
+            scheme => Err(EndpointError::UnknownScheme(scheme.into())),
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp { addr, port } if addr.contains(':') => write!(f, "tcp://[{addr}]:{port}"),
+            Self::Tcp { addr, port } => write!(f, "tcp://{addr}:{port}"),
+            Self::Unix(path) => write!(f, "unix://{path}", path = path.display()),
+            Self::Vsock { cid, port } => write!(f, "vsock://{cid}:{port}"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Endpoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
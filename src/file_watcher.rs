@@ -0,0 +1,136 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// Files are often rewritten in several steps, e.g. a certificate rotation rewrites the CA,
+/// certificate and key one after another, reload once the events stop
+const RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::subclass::prelude::*;
+    use gtk::{gio, glib};
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    #[derive(Default)]
+    pub struct FileWatcher {
+        pub(super) monitors: RefCell<Vec<gio::FileMonitor>>,
+        /// Reads the files again, true when what was read differs from before
+        pub(super) reload: RefCell<Option<Box<dyn Fn() -> bool>>>,
+        pub(super) reload_timeout: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FileWatcher {
+        const NAME: &'static str = "FileWatcher";
+        type Type = super::FileWatcher;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for FileWatcher {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Files reloaded whenever they change on disk, "changed" is emitted when the reload found
+    /// something new
+    pub struct FileWatcher(ObjectSubclass<imp::FileWatcher>);
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl FileWatcher {
+    /// Calls `reload` whenever one of `paths` changes on disk, replacing the previous files.
+    /// `reload` returns whether what it read differs from before.
+    pub fn watch(&self, paths: &[&Path], reload: impl Fn() -> bool + 'static) {
+        let imp = self.imp();
+        imp.monitors.borrow_mut().clear();
+        if let Some(source) = imp.reload_timeout.take() {
+            source.remove();
+        }
+        imp.reload.replace(Some(Box::new(reload)));
+
+        for path in paths {
+            let monitor = match gio::File::for_path(path)
+                .monitor_file(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+            {
+                Ok(monitor) => monitor,
+                Err(e) => {
+                    warn!("Cannot watch {path}: {e}", path = path.display());
+                    continue;
+                }
+            };
+            monitor.connect_changed(glib::clone!(
+                #[weak(rename_to = watcher)]
+                self,
+                move |_, file, _, event| {
+                    if matches!(
+                        event,
+                        gio::FileMonitorEvent::ChangesDoneHint
+                            | gio::FileMonitorEvent::Created
+                            | gio::FileMonitorEvent::Deleted
+                            | gio::FileMonitorEvent::Renamed
+                            | gio::FileMonitorEvent::MovedIn
+                            | gio::FileMonitorEvent::MovedOut
+                    ) {
+                        debug!("File {file} changed", file = file.parse_name());
+                        watcher.schedule_reload();
+                    }
+                }
+            ));
+            imp.monitors.borrow_mut().push(monitor);
+        }
+    }
+
+    fn schedule_reload(&self) {
+        if let Some(source) = self.imp().reload_timeout.take() {
+            source.remove();
+        }
+        let source = glib::timeout_add_local_once(
+            RELOAD_DELAY,
+            glib::clone!(
+                #[weak(rename_to = watcher)]
+                self,
+                move || {
+                    watcher.imp().reload_timeout.take();
+                    watcher.reload();
+                }
+            ),
+        );
+        self.imp().reload_timeout.replace(Some(source));
+    }
+
+    /// Reads the files now, emitting "changed" when something differs
+    pub fn reload(&self) {
+        let changed = self
+            .imp()
+            .reload
+            .borrow()
+            .as_ref()
+            .is_some_and(|reload| reload());
+        if changed {
+            self.emit_by_name::<()>("changed", &[]);
+        }
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_local("changed", false, move |values| {
+            let watcher = values[0].get::<Self>().unwrap();
+            f(&watcher);
+            None
+        })
+    }
+}
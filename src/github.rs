@@ -8,12 +8,15 @@ use http::header::ACCEPT;
 use octocrab::{Octocrab, models::issues::Issue};
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize, Serializer};
-use std::path::{Path, PathBuf};
-use std::{env, time::Duration};
+use std::time::Duration;
 use thiserror::Error as ThisError;
 
+use crate::config::{Config, ConfigError};
 use crate::prelude::*;
 
+/// Table of the user configuration holding the bug reporter settings
+const CONFIG_SECTION: &str = "github";
+
 #[derive(ThisError, Debug)]
 pub enum Error {
     Cancelled,
@@ -31,6 +34,8 @@ pub enum Error {
     TomlDe(#[from] toml::de::Error),
     #[error(transparent)]
     TomlSer(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
 }
 
 impl std::fmt::Display for Error {
@@ -116,23 +121,15 @@ pub async fn auth(config: &mut GithubConfig) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn get_config_path() -> PathBuf {
-    let variable_name = "GITHUB_CONFIG";
-    let variable = env::var_os(variable_name);
-
-    if let Some(val) = variable {
-        PathBuf::from(val)
-    } else {
-        warn!("Missing environment variable: {variable_name}");
-        Path::new(&env::var_os("HOME").unwrap_or_else(|| "/home/ghaf".into()))
-            .join(".config/ctrl-panel/config.toml")
-    }
-}
-
+/// Reads the `[github]` table of the user configuration. Older files kept these keys at the
+/// top level, they are used until a new token gets stored.
 pub fn load_config() -> Result<GithubConfig, Error> {
-    let path = get_config_path();
-
-    Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    let mut table = Config::read_user_layer()?;
+    let config = match table.remove(CONFIG_SECTION) {
+        Some(section) => section.try_into()?,
+        None => table.try_into()?,
+    };
+    Ok(config)
 }
 
 pub async fn create_github_issue(title: String, content: String) -> Result<Issue, Error> {
@@ -173,9 +170,15 @@ async fn send_issue(config: &GithubConfig, title: &str, body: &str) -> Result<Is
 #[inline]
 fn set_key(config: &mut GithubConfig, token: SecretString) -> Result<(), Error> {
     config.token = Some(token);
-    let path = get_config_path();
+    let section = toml::Table::try_from(&*config)?;
 
-    std::fs::write(&path, toml::to_string(config)?.as_bytes())?;
+    Config::update_user_layer(|table| {
+        // Drops the top-level keys of older files
+        for key in section.keys() {
+            table.remove(key);
+        }
+        table.insert(CONFIG_SECTION.into(), section.into());
+    })?;
 
     Ok(())
 }
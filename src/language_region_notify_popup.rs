@@ -1,17 +1,51 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
+use std::fmt::Display;
+
+/// Outcome of applying one Language & Region setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingResult {
+    /// Already in effect, not applied again
+    Unchanged,
+    Applied,
+    Failed(String),
+}
+
+impl<E: Display> From<Result<(), E>> for SettingResult {
+    fn from(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Self::Applied,
+            Err(e) => Self::Failed(e.to_string()),
+        }
+    }
+}
 
 mod imp {
     use glib::Binding;
     use glib::subclass::Signal;
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
-    use gtk::{CompositeTemplate, glib};
-    use std::cell::RefCell;
+    use gtk::{Button, CompositeTemplate, Grid, Label, glib};
+    use std::cell::{Cell, RefCell};
     use std::sync::OnceLock;
 
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/language_region_notify_popup.ui")]
     pub struct LanguageRegionNotifyPopup {
+        #[template_child]
+        pub title_label: TemplateChild<Label>,
+        #[template_child]
+        pub results_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub restart_label: TemplateChild<Label>,
+        #[template_child]
+        pub ok_button: TemplateChild<Button>,
+        #[template_child]
+        pub log_out_button: TemplateChild<Button>,
+
+        pub(super) rows: Cell<i32>,
+
         // Vector holding the bindings to properties of `Object`
         pub bindings: RefCell<Vec<Binding>>,
     }
@@ -38,6 +72,12 @@ mod imp {
         fn on_ok_clicked(&self) {
             self.obj().close();
         }
+
+        #[template_callback]
+        fn on_log_out_clicked(&self) {
+            self.obj().emit_by_name::<()>("log-out", &[]);
+            self.obj().close();
+        }
     } //end #[gtk::template_callbacks]
 
     impl ObjectImpl for LanguageRegionNotifyPopup {
@@ -48,7 +88,12 @@ mod imp {
 
         fn signals() -> &'static [Signal] {
             static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
-            SIGNALS.get_or_init(|| vec![Signal::builder("reset-default").build()])
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("reset-default").build(),
+                    Signal::builder("log-out").build(),
+                ]
+            })
         }
     }
     impl WidgetImpl for LanguageRegionNotifyPopup {}
@@ -73,4 +118,39 @@ impl LanguageRegionNotifyPopup {
     pub fn new() -> Self {
         glib::Object::builder().build()
     }
+
+    /// Lists the outcome of `setting`, e.g. "Timezone"
+    pub fn add_result(&self, setting: &str, result: &SettingResult) {
+        let imp = self.imp();
+        let (text, css_class) = match result {
+            SettingResult::Unchanged => (String::from("Unchanged"), "dim-label"),
+            SettingResult::Applied => (String::from("Applied"), "success"),
+            SettingResult::Failed(error) => (format!("Failed: {error}"), "error"),
+        };
+        let row = imp.rows.get();
+        let name = gtk::Label::builder().label(setting).xalign(0.).build();
+        let outcome = gtk::Label::builder()
+            .label(text)
+            .xalign(0.)
+            .wrap(true)
+            .max_width_chars(50)
+            .css_classes([css_class])
+            .build();
+        imp.results_grid.attach(&name, 0, row, 1, 1);
+        imp.results_grid.attach(&outcome, 1, row, 1, 1);
+        imp.rows.set(row + 1);
+
+        if matches!(result, SettingResult::Failed(_)) {
+            imp.title_label
+                .set_label("Some Language & Region settings could not be applied");
+        }
+    }
+
+    /// Asks to log out, offering to do it right away
+    pub fn set_restart_needed(&self, needed: bool) {
+        let imp = self.imp();
+        imp.restart_label.set_visible(needed);
+        imp.log_out_button.set_visible(needed);
+        imp.ok_button.set_label(if needed { "Later" } else { "OK" });
+    }
 }
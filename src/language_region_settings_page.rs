@@ -1,11 +1,47 @@
-use gio::ListModel;
+use gio::ListStore;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
-use gtk::{gio, glib};
+use gtk::{CustomSorter, SortListModel, gio, glib};
 
 use crate::data_gobject::DataGObject;
+use crate::locale_provider::{KeyboardLayout, KeyboardLayouts, LanguageRegionEntry, RegionChange};
 use crate::prelude::*;
 
+/// Group of the copies listed first, see `LanguageRegionEntry::suggested`
+const SUGGESTED_GROUP: &str = "Suggested";
+
+/// Entries sectioned by group, suggested copies coming first with `current` at their top
+fn grouped_model(entries: Vec<LanguageRegionEntry>, current: Option<&str>) -> SortListModel {
+    let mut suggested: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.suggested)
+        .cloned()
+        .map(|entry| LanguageRegionEntry {
+            group: SUGGESTED_GROUP.into(),
+            ..entry
+        })
+        .collect();
+    suggested.sort_by_key(|entry| Some(entry.code.as_str()) != current);
+    let store: ListStore = suggested
+        .into_iter()
+        .chain(entries)
+        .map(DataGObject::from)
+        .collect();
+
+    let model = SortListModel::new(Some(store), None::<CustomSorter>);
+    model.set_section_sorter(Some(&CustomSorter::new(|a, b| {
+        let key = |obj: &glib::Object| {
+            let group = obj
+                .downcast_ref::<DataGObject>()
+                .map(DataGObject::group)
+                .unwrap_or_default();
+            (group != SUGGESTED_GROUP, group)
+        };
+        key(a).cmp(&key(b)).into()
+    })));
+    model
+}
+
 //+list of supported resolutions/modes ?
 
 mod imp {
@@ -13,11 +49,13 @@ mod imp {
     use glib::subclass::Signal;
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
-    use gtk::{CompositeTemplate, DropDown, glib};
+    use gtk::{CompositeTemplate, DropDown, Entry, glib};
     use std::cell::RefCell;
+    use std::collections::BTreeMap;
     use std::sync::OnceLock;
 
     use crate::data_gobject::DataGObject;
+    use crate::locale_provider::LanguageRegionEntry;
     use crate::prelude::*;
 
     #[derive(Default, CompositeTemplate)]
@@ -26,9 +64,21 @@ mod imp {
         #[template_child]
         pub language_switch: TemplateChild<DropDown>,
 
+        #[template_child]
+        pub keyboard_layout_switch: TemplateChild<DropDown>,
+
+        #[template_child]
+        pub keyboard_variant_switch: TemplateChild<DropDown>,
+
+        #[template_child]
+        pub keyboard_preview_entry: TemplateChild<Entry>,
+
         #[template_child]
         pub timezone_switch: TemplateChild<DropDown>,
 
+        /// Variants of each keyboard layout
+        pub(super) keyboard_variants: RefCell<BTreeMap<String, Vec<LanguageRegionEntry>>>,
+
         // Vector holding the bindings to properties of `Object`
         pub bindings: RefCell<Vec<Binding>>,
     }
@@ -68,9 +118,47 @@ mod imp {
                 .selected_obj()
                 .as_ref()
                 .map_or_else(|| "UTC".into(), DataGObject::name);
-            debug!("Language and timezone changed! {locale}, {timezone}");
-            self.obj()
-                .emit_by_name::<()>("locale-timezone-changed", &[&locale, &timezone]);
+            let layout = self
+                .keyboard_layout_switch
+                .selected_obj()
+                .as_ref()
+                .map_or_else(|| "us".into(), DataGObject::name);
+            let variant = self
+                .keyboard_variant_switch
+                .selected_obj()
+                .as_ref()
+                .map(DataGObject::name)
+                .unwrap_or_default();
+            debug!("Language and timezone changed! {locale}, {timezone}, {layout} {variant}");
+            self.obj().emit_by_name::<()>(
+                "locale-timezone-changed",
+                &[&locale, &timezone, &layout, &variant],
+            );
+        }
+
+        /// Lists the variants of the selected layout, the layout itself coming first
+        #[template_callback]
+        fn on_keyboard_layout_selected(&self) {
+            let layout = self
+                .keyboard_layout_switch
+                .selected_obj()
+                .as_ref()
+                .map(DataGObject::name)
+                .unwrap_or_default();
+            let model: gtk::gio::ListStore =
+                std::iter::once(DataGObject::new(String::new(), String::from("Default")))
+                    .chain(
+                        self.keyboard_variants
+                            .borrow()
+                            .get(&layout)
+                            .into_iter()
+                            .flatten()
+                            .cloned()
+                            .map(DataGObject::from),
+                    )
+                    .collect();
+            self.keyboard_variant_switch.set_model(Some(&model));
+            self.keyboard_variant_switch.set_selected(0);
         }
     } //end #[gtk::template_callbacks]
 
@@ -84,8 +172,14 @@ mod imp {
             static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
             SIGNALS.get_or_init(|| {
                 vec![
+                    // Locale, timezone, keyboard layout and variant
                     Signal::builder("locale-timezone-changed")
-                        .param_types([String::static_type(), String::static_type()])
+                        .param_types([
+                            String::static_type(),
+                            String::static_type(),
+                            String::static_type(),
+                            String::static_type(),
+                        ])
                         .build(),
                     Signal::builder("locale-default").build(),
                 ]
@@ -113,11 +207,11 @@ impl LanguageRegionSettingsPage {
         glib::Object::builder().build()
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    pub fn set_locale_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
+    pub fn set_locales(&self, locales: Vec<LanguageRegionEntry>, current: Option<&str>) {
+        let model = grouped_model(locales, current);
         self.imp().language_switch.set_model(Some(&model));
-        if let Some(idx) = selected {
-            self.imp().language_switch.set_selected(idx as u32);
+        if let Some(current) = current {
+            self.locale_select_find(|obj| obj.name() == current);
         }
     }
 
@@ -133,11 +227,11 @@ impl LanguageRegionSettingsPage {
         }
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    pub fn set_timezone_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
+    pub fn set_timezones(&self, timezones: Vec<LanguageRegionEntry>, current: Option<&str>) {
+        let model = grouped_model(timezones, current);
         self.imp().timezone_switch.set_model(Some(&model));
-        if let Some(idx) = selected {
-            self.imp().timezone_switch.set_selected(idx as u32);
+        if let Some(current) = current {
+            self.timezone_select_find(|obj| obj.name() == current);
         }
     }
 
@@ -152,4 +246,65 @@ impl LanguageRegionSettingsPage {
             self.imp().timezone_switch.set_selected(index as u32);
         }
     }
+
+    pub fn set_keyboard_layouts(
+        &self,
+        keyboard: KeyboardLayouts,
+        current: Option<&KeyboardLayout>,
+    ) {
+        let imp = self.imp();
+        imp.keyboard_variants.replace(keyboard.variants);
+        let model: ListStore = keyboard
+            .layouts
+            .into_iter()
+            .map(DataGObject::from)
+            .collect();
+        imp.keyboard_layout_switch.set_model(Some(&model));
+        if let Some(current) = current {
+            self.keyboard_select(current);
+        }
+    }
+
+    /// Selects `keyboard`, the variant list following the layout
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn keyboard_select(&self, keyboard: &KeyboardLayout) {
+        let imp = self.imp();
+        let find = |switch: &gtk::DropDown, code: &str| {
+            switch.model().and_then(|m| {
+                m.wrap::<DataGObject>()
+                    .iter()
+                    .position(|item| item.name() == code)
+            })
+        };
+        if let Some(index) = find(&imp.keyboard_layout_switch, &keyboard.layout) {
+            imp.keyboard_layout_switch.set_selected(index as u32);
+        }
+        if let Some(index) = find(&imp.keyboard_variant_switch, &keyboard.variant) {
+            imp.keyboard_variant_switch.set_selected(index as u32);
+        }
+    }
+
+    /// Follows a setting changed outside the page
+    pub fn show_region_change(&self, change: &RegionChange) {
+        match change {
+            RegionChange::Locale(locale) => self.locale_select_find(|obj| obj.name() == *locale),
+            RegionChange::Keyboard(keyboard) => {
+                self.keyboard_select(keyboard);
+                let applied = self
+                    .imp()
+                    .keyboard_layout_switch
+                    .selected_obj()
+                    .as_ref()
+                    .map_or_else(|| keyboard.layout.clone(), DataGObject::display);
+                self.imp()
+                    .keyboard_preview_entry
+                    .set_placeholder_text(Some(&format!("Type here to try {applied}")));
+            }
+            RegionChange::Timezone(timezone) => {
+                self.timezone_select_find(|obj| obj.name() == *timezone);
+            }
+            // Shown by the Date & Time page
+            RegionChange::Clock(_) => {}
+        }
+    }
 }
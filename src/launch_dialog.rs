@@ -0,0 +1,156 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{
+        Button, CompositeTemplate, DropDown, Entry, Label, ListBox, ListBoxRow, StringObject, glib,
+    };
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use crate::prelude::*;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/launch_dialog.ui")]
+    pub struct LaunchDialog {
+        #[template_child]
+        pub title_label: TemplateChild<Label>,
+        #[template_child]
+        pub args_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub error_label: TemplateChild<Label>,
+        #[template_child]
+        pub vm_switch: TemplateChild<DropDown>,
+        #[template_child]
+        pub history_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub history_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub launch_button: TemplateChild<Button>,
+
+        pub app_name: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LaunchDialog {
+        const NAME: &'static str = "LaunchDialog";
+        type Type = super::LaunchDialog;
+        type ParentType = gtk::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl LaunchDialog {
+        #[template_callback]
+        fn on_args_changed(&self) {
+            let valid = shlex::split(&self.args_entry.text()).is_some();
+            self.error_label.set_visible(!valid);
+            self.launch_button.set_sensitive(valid);
+        }
+
+        #[template_callback]
+        fn on_history_row_activated(&self, row: &ListBoxRow) {
+            if let Some(label) = row.child().and_downcast::<Label>() {
+                self.args_entry.set_text(&label.label());
+            }
+        }
+
+        #[template_callback]
+        fn on_launch_clicked(&self) {
+            let Some(args) = shlex::split(&self.args_entry.text()) else {
+                return;
+            };
+            let Some(vm) = self
+                .vm_switch
+                .selected_obj::<StringObject>()
+                .map(|vm| vm.string().to_string())
+            else {
+                return;
+            };
+            let app = self.app_name.borrow().clone();
+            debug!("Launching {app} in {vm} with {args:?}");
+            self.obj()
+                .emit_by_name::<()>("launch-requested", &[&app, &vm, &args]);
+            self.obj().close();
+        }
+
+        #[template_callback]
+        fn on_cancel_clicked(&self) {
+            self.obj().close();
+        }
+    } //end #[gtk::template_callbacks]
+
+    impl ObjectImpl for LaunchDialog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("launch-requested")
+                        .param_types([
+                            String::static_type(),
+                            String::static_type(),
+                            Vec::<String>::static_type(),
+                        ])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for LaunchDialog {}
+    impl WindowImpl for LaunchDialog {}
+}
+
+glib::wrapper! {
+pub struct LaunchDialog(ObjectSubclass<imp::LaunchDialog>)
+    @extends gtk::Widget, gtk::Window,
+    @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+        gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl LaunchDialog {
+    /// `vms` lists the VMs able to run `app`, the first one is preselected.
+    /// `history` holds recently used argument sets, most recent first.
+    pub fn new(app: &str, vms: &[String], history: &[Vec<String>]) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+        let imp = dialog.imp();
+        *imp.app_name.borrow_mut() = app.to_owned();
+        imp.title_label.set_label(&format!("Launch {app} with…"));
+
+        let vms = vms.iter().map(String::as_str).collect::<Vec<_>>();
+        imp.vm_switch.set_model(Some(&gtk::StringList::new(&vms)));
+
+        imp.history_box.set_visible(!history.is_empty());
+        for args in history {
+            let label = gtk::Label::builder()
+                .label(join_args(args).as_str())
+                .halign(gtk::Align::Start)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .margin_start(10)
+                .margin_top(5)
+                .margin_bottom(5)
+                .build();
+            imp.history_list.append(&label);
+        }
+        if let Some(args) = history.first() {
+            imp.args_entry.set_text(&join_args(args));
+        }
+
+        dialog
+    }
+}
+
+fn join_args(args: &[String]) -> String {
+    shlex::try_join(args.iter().map(String::as_str)).unwrap_or_else(|_| args.join(" "))
+}
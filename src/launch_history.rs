@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::prelude::*;
+use crate::xdg;
+
+/// Number of argument sets remembered per application
+const MAX_ENTRIES: usize = 10;
+
+/// Recently used launch arguments, most recent first, keyed by application name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LaunchHistory {
+    #[serde(flatten)]
+    apps: BTreeMap<String, Vec<Vec<String>>>,
+}
+
+impl LaunchHistory {
+    fn path() -> PathBuf {
+        xdg::state_dir().join("launch-history.json")
+    }
+
+    pub fn load() -> Self {
+        let path = Self::path();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(
+                    "Ignoring malformed launch history {path}: {e}",
+                    path = path.display()
+                );
+                Self::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!(
+                    "Failed to read launch history {path}: {e}",
+                    path = path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self, app: &str) -> &[Vec<String>] {
+        self.apps.get(app).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn record(&mut self, app: &str, args: Vec<String>) {
+        if args.is_empty() {
+            return;
+        }
+        let entries = self.apps.entry(app.to_owned()).or_default();
+        entries.retain(|entry| *entry != args);
+        entries.insert(0, args);
+        entries.truncate(MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,167 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::app_catalog::CatalogEntry;
+
+/// Suffixes of the bundled `icon_app_*.svg` icons
+const BUNDLED_APP_ICONS: [&str; 3] = ["chrome", "pdf", "whatsapp"];
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{CompositeTemplate, Label, glib};
+    use std::sync::OnceLock;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/launcher_page.ui")]
+    pub struct LauncherPage {
+        #[template_child]
+        pub empty_label: TemplateChild<Label>,
+        #[template_child]
+        pub vm_sections: TemplateChild<gtk::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LauncherPage {
+        const NAME: &'static str = "LauncherPage";
+        type Type = super::LauncherPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for LauncherPage {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("launch-app")
+                        .param_types([
+                            String::static_type(),
+                            String::static_type(),
+                            Vec::<String>::static_type(),
+                        ])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for LauncherPage {}
+    impl BoxImpl for LauncherPage {}
+}
+
+glib::wrapper! {
+pub struct LauncherPage(ObjectSubclass<imp::LauncherPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for LauncherPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LauncherPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_apps(&self, apps: &[CatalogEntry]) {
+        let sections = self.imp().vm_sections.get();
+        while let Some(child) = sections.first_child() {
+            sections.remove(&child);
+        }
+        self.imp().empty_label.set_visible(apps.is_empty());
+
+        let mut by_vm = BTreeMap::<&str, Vec<&CatalogEntry>>::new();
+        for app in apps {
+            by_vm.entry(&app.vm).or_default().push(app);
+        }
+
+        for (vm, apps) in by_vm {
+            let title = gtk::Label::builder()
+                .label(vm)
+                .halign(gtk::Align::Start)
+                .css_classes(["vm-name"])
+                .build();
+            let flow_box = gtk::FlowBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .homogeneous(true)
+                .max_children_per_line(6)
+                .column_spacing(10)
+                .row_spacing(10)
+                .build();
+            for app in apps {
+                flow_box.append(&self.app_button(app));
+            }
+            sections.append(&title);
+            sections.append(&flow_box);
+        }
+    }
+
+    fn app_button(&self, app: &CatalogEntry) -> gtk::Button {
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(5)
+            .margin_top(5)
+            .margin_bottom(5)
+            .build();
+        content.append(&app_icon(app));
+        content.append(
+            &gtk::Label::builder()
+                .label(app.label())
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .max_width_chars(14)
+                .build(),
+        );
+
+        let button = gtk::Button::builder()
+            .child(&content)
+            .tooltip_text(format!(
+                "Start {name} in {vm}",
+                name = app.label(),
+                vm = app.vm
+            ))
+            .css_classes(["settings-button"])
+            .build();
+        let (name, vm, args) = (app.name.clone(), app.vm.clone(), app.args.clone());
+        button.connect_clicked(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_| {
+                page.emit_by_name::<()>("launch-app", &[&name, &vm, &args]);
+            }
+        ));
+        button
+    }
+}
+
+fn app_icon(app: &CatalogEntry) -> gtk::Image {
+    let icon = app.icon.as_deref().unwrap_or(&app.name);
+    let image = if let Some(bundled) = BUNDLED_APP_ICONS
+        .iter()
+        .find(|bundled| icon.contains(*bundled))
+    {
+        gtk::Image::from_resource(&format!(
+            "/ae/tii/ghaf/controlpanelgui/icons/icon_app_{bundled}.svg"
+        ))
+    } else if gtk::gdk::Display::default()
+        .is_some_and(|display| gtk::IconTheme::for_display(&display).has_icon(icon))
+    {
+        gtk::Image::from_icon_name(icon)
+    } else {
+        gtk::Image::from_icon_name("application-x-executable")
+    };
+    image.set_pixel_size(48);
+    image
+}
@@ -1,41 +1,137 @@
+use futures::StreamExt;
+use futures::stream;
+use gtk::glib;
+use std::collections::BTreeMap;
 use std::process::Command;
+use zbus::{Connection, proxy};
 
 use crate::data_gobject::DataGObject;
 use crate::prelude::*;
 
+/// Layouts and variants known to xkb
+const XKB_RULES_FILE: &str = "/usr/share/X11/xkb/rules/evdev.lst";
+/// Entries suggested above the full locale and timezone lists, the current one included
+const MAX_SUGGESTED: usize = 8;
+/// Group of timezones outside any continent, such as `UTC`
+const OTHER_TIMEZONES: &str = "Other";
+
+#[proxy(
+    interface = "org.freedesktop.locale1",
+    default_service = "org.freedesktop.locale1",
+    default_path = "/org/freedesktop/locale1",
+    gen_blocking = false
+)]
+trait Locale1 {
+    /// Environment assignments such as `LANG=en_US.UTF-8`
+    #[zbus(property)]
+    fn locale(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property, name = "VConsoleKeymap")]
+    fn vconsole_keymap(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "X11Layout")]
+    fn x11_layout(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "X11Variant")]
+    fn x11_variant(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "X11Model")]
+    fn x11_model(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "X11Options")]
+    fn x11_options(&self) -> zbus::Result<String>;
+
+    #[zbus(name = "SetX11Keyboard")]
+    fn set_x11_keyboard(
+        &self,
+        layout: &str,
+        model: &str,
+        variant: &str,
+        options: &str,
+        convert: bool,
+        interactive: bool,
+    ) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.timedate1",
+    default_service = "org.freedesktop.timedate1",
+    default_path = "/org/freedesktop/timedate1",
+    gen_blocking = false
+)]
+trait Timedate1 {
+    fn list_timezones(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property)]
+    fn timezone(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "NTP")]
+    fn ntp(&self) -> zbus::Result<bool>;
+
+    #[zbus(property, name = "NTPSynchronized")]
+    fn ntp_synchronized(&self) -> zbus::Result<bool>;
+}
+
+/// X11 keyboard layout with its variant, empty for the default one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    pub layout: String,
+    pub variant: String,
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self {
+            layout: String::from("us"),
+            variant: String::new(),
+        }
+    }
+}
+
+/// Layouts to pick from, with the variants of each layout
+#[derive(Default)]
+pub struct KeyboardLayouts {
+    pub layouts: Vec<LanguageRegionEntry>,
+    pub variants: BTreeMap<String, Vec<LanguageRegionEntry>>,
+}
+
+/// Automatic time synchronization as seen by `timedated`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClockStatus {
+    /// NTP is enabled
+    pub ntp: bool,
+    /// The system clock is synchronized with a time server
+    pub synchronized: bool,
+}
+
+/// Locale, keyboard layout and timezone in effect, `None` where unknown
+#[derive(Debug, Default, Clone)]
+pub struct RegionSettings {
+    pub locale: Option<String>,
+    pub keyboard: Option<KeyboardLayout>,
+    pub timezone: Option<String>,
+}
+
+/// Setting changed through `localed` or `timedated`, e.g. by another session
+#[derive(Debug, Clone)]
+pub enum RegionChange {
+    Locale(String),
+    Keyboard(KeyboardLayout),
+    Timezone(String),
+    Clock(ClockStatus),
+}
+
+/// Locales from `locale -va`, the rest from `org.freedesktop.locale1` and
+/// `org.freedesktop.timedate1` on the system bus
 pub struct LocaleProvider();
 
 impl LocaleProvider {
-    fn path_join<P: AsRef<std::path::Path>, Pa: AsRef<std::path::Path>>(
-        base: P,
-        rel: Pa,
-    ) -> std::path::PathBuf {
-        use std::path::Component::{CurDir, Normal, ParentDir, Prefix, RootDir};
-        base.as_ref()
-            .components()
-            .chain(rel.as_ref().components())
-            .fold(Vec::new(), |mut path, part| {
-                match part {
-                    Prefix(_) => {
-                        path.clear();
-                        path.push(part);
-                    }
-                    RootDir => {
-                        path.retain(|p| matches!(p, Prefix(_)));
-                        path.push(part);
-                    }
-                    CurDir => {}
-                    ParentDir => {
-                        if !matches!(path.last(), Some(Prefix(_) | RootDir)) {
-                            path.pop();
-                        }
-                    }
-                    part @ Normal(_) => path.push(part),
-                }
-                path
-            })
-            .into_iter()
-            .collect()
+    async fn connect() -> zbus::Result<(Locale1Proxy<'static>, Timedate1Proxy<'static>)> {
+        let connection = Connection::system().await?;
+        Ok((
+            Locale1Proxy::new(&connection).await?,
+            Timedate1Proxy::new(&connection).await?,
+        ))
     }
 
     fn get_locales() -> Result<Vec<LanguageRegionEntry>, Box<dyn std::error::Error>> {
@@ -59,6 +155,7 @@ impl LocaleProvider {
                     .next()
                     .is_some_and(|c| c.is_ascii_lowercase())
             {
+                let group = lang.clone().unwrap_or_else(|| locale.clone());
                 let lang = lang.map_or_else(
                     || locale.clone(),
                     |lang| {
@@ -72,6 +169,8 @@ impl LocaleProvider {
                 locales.push(LanguageRegionEntry {
                     code: locale,
                     display: lang,
+                    group,
+                    suggested: false,
                 });
             }
             if let Some(loc) = line
@@ -89,115 +188,403 @@ impl LocaleProvider {
         Ok(locales)
     }
 
-    fn get_current_timezone() -> Result<String, Box<dyn std::error::Error>> {
-        let p = Self::path_join("/etc", std::fs::read_link("/etc/localtime")?);
-        Ok(p.strip_prefix("/etc/zoneinfo")?
-            .to_str()
-            .ok_or("Invalid characters in timezone")?
-            .to_owned())
+    /// Layouts and variants from the `! layout` and `! variant` sections of the xkb rules list,
+    /// variant lines being `<code> <layout>: <description>`
+    fn get_keyboard_layouts() -> Result<KeyboardLayouts, std::io::Error> {
+        let mut keyboard = KeyboardLayouts::default();
+        let mut section = "";
+        for line in std::fs::read_to_string(XKB_RULES_FILE)?.lines() {
+            if let Some(name) = line.strip_prefix("! ") {
+                section = name.trim();
+                continue;
+            }
+            let Some((code, description)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let description = description.trim();
+            match section {
+                "layout" => keyboard.layouts.push((code, description).into()),
+                "variant" => {
+                    if let Some((layout, description)) = description.split_once(": ") {
+                        keyboard
+                            .variants
+                            .entry(layout.to_owned())
+                            .or_default()
+                            .push((code, description).into());
+                    }
+                }
+                _ => {}
+            }
+        }
+        keyboard.layouts.sort_by(|a, b| a.display.cmp(&b.display));
+        Ok(keyboard)
     }
 
-    fn get_current_locale() -> Result<String, Box<dyn std::error::Error>> {
-        std::fs::read_to_string("/etc/locale.conf")?
-            .lines()
-            .find_map(|line| {
-                line.split_once('=')
-                    .and_then(|(var, val)| (var == "LANG").then(|| val.to_owned()))
-            })
-            .ok_or_else(|| "LANG not found".into())
-            .map(|s| {
-                s.split_once('.')
-                    .map(|(loc, cset)| {
-                        loc.chars()
-                            .chain(std::iter::once('.'))
-                            .chain(cset.chars().filter_map(|c| match c {
-                                c if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
-                                _ => None,
-                            }))
-                            .collect::<String>()
-                    })
-                    .unwrap_or(s)
-            })
-    }
-
-    fn get_timezone_display(tz: &str) -> String {
-        tz.chars().map(|c| if c == '_' { ' ' } else { c }).collect()
-    }
-
-    fn get_timezones() -> Result<Vec<LanguageRegionEntry>, Box<dyn std::error::Error>> {
-        let output = Command::new("timedatectl").arg("list-timezones").output()?;
-        Ok(String::from_utf8(output.stdout)?
-            .lines()
-            .map(|tz| (tz, Self::get_timezone_display(tz)).into())
-            .collect())
+    /// `LANG` of localed assignments, with the charset spelled as by `locale -a`, e.g. `en_US.utf8`
+    fn lang_of(assignments: &[String]) -> Option<String> {
+        let lang = assignments
+            .iter()
+            .find_map(|assignment| assignment.strip_prefix("LANG="))?;
+        Some(match lang.split_once('.') {
+            Some((locale, charset)) => {
+                let charset: String = charset
+                    .chars()
+                    .filter(char::is_ascii_alphanumeric)
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect();
+                format!("{locale}.{charset}")
+            }
+            None => lang.to_owned(),
+        })
+    }
+
+    async fn get_current_locale(locale1: &Locale1Proxy<'_>) -> Option<String> {
+        match locale1.locale().await {
+            Ok(assignments) => Self::lang_of(&assignments),
+            Err(e) => {
+                warn!("Error detecting current locale: {e}");
+                None
+            }
+        }
+    }
+
+    /// X11 layout used by graphical sessions, or the console keymap if there is none.
+    /// Of several configured layouts, the first one is the default.
+    async fn get_current_keyboard(locale1: &Locale1Proxy<'_>) -> Option<KeyboardLayout> {
+        let first = |value: String| value.split(',').next().unwrap_or_default().to_owned();
+        let keyboard = match locale1.x11_layout().await {
+            Ok(layout) if !layout.is_empty() => {
+                locale1.x11_variant().await.map(|variant| KeyboardLayout {
+                    layout: first(layout),
+                    variant: first(variant),
+                })
+            }
+            Ok(_) => locale1
+                .vconsole_keymap()
+                .await
+                .map(|layout| KeyboardLayout {
+                    layout,
+                    variant: String::new(),
+                }),
+            Err(e) => Err(e),
+        };
+        match keyboard {
+            Ok(keyboard) if !keyboard.layout.is_empty() => Some(keyboard),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Error detecting current keyboard layout: {e}");
+                None
+            }
+        }
+    }
+
+    /// Applies `keyboard` through localed, which also converts it to a console keymap.
+    /// The keyboard model and options are kept.
+    pub async fn set_keyboard(keyboard: &KeyboardLayout) -> zbus::Result<()> {
+        let (locale1, _) = Self::connect().await?;
+        let model = locale1.x11_model().await?;
+        let options = locale1.x11_options().await?;
+        locale1
+            .set_x11_keyboard(
+                &keyboard.layout,
+                &model,
+                &keyboard.variant,
+                &options,
+                true,
+                true,
+            )
+            .await
+    }
+
+    async fn get_current_timezone(timedate1: &Timedate1Proxy<'_>) -> Option<String> {
+        match timedate1.timezone().await {
+            Ok(timezone) if !timezone.is_empty() => Some(timezone),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Error detecting current timezone: {e}");
+                None
+            }
+        }
+    }
+
+    async fn read_clock_status(timedate1: &Timedate1Proxy<'_>) -> zbus::Result<ClockStatus> {
+        Ok(ClockStatus {
+            ntp: timedate1.ntp().await?,
+            synchronized: timedate1.ntp_synchronized().await?,
+        })
+    }
+
+    pub async fn get_clock_status() -> zbus::Result<ClockStatus> {
+        let (_, timedate1) = Self::connect().await?;
+        Self::read_clock_status(&timedate1).await
+    }
+
+    /// Language and territory codes of a locale, `de` and `CH` for `de_CH.utf8`
+    fn locale_parts(locale: &str) -> (&str, Option<&str>) {
+        let name = locale.split(['.', '@']).next().unwrap_or(locale);
+        match name.split_once('_') {
+            Some((lang, territory)) => (lang, Some(territory)),
+            None => (name, None),
+        }
+    }
+
+    /// Marks the current locale and those sharing its language or territory as suggested
+    fn suggest_locales(locales: &mut [LanguageRegionEntry], current: Option<&str>) {
+        let Some(current) = current else {
+            return;
+        };
+        let (lang, territory) = Self::locale_parts(current);
+        let related = |code: &str| {
+            let (other_lang, other_territory) = Self::locale_parts(code);
+            other_lang == lang || (territory.is_some() && other_territory == territory)
+        };
+        Self::suggest(locales, current, related);
+    }
+
+    /// Marks `current` and up to [`MAX_SUGGESTED`] entries in all related to it as suggested
+    fn suggest(entries: &mut [LanguageRegionEntry], current: &str, related: impl Fn(&str) -> bool) {
+        let mut left = MAX_SUGGESTED;
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.code == current) {
+            entry.suggested = true;
+            left -= 1;
+        }
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| entry.code != current && related(&entry.code))
+            .take(left)
+        {
+            entry.suggested = true;
+        }
+    }
+
+    /// Current offset from UTC of `tz` in seconds
+    fn utc_offset(tz: &str) -> Option<i32> {
+        let zone = glib::TimeZone::from_identifier(Some(tz))?;
+        let now = glib::DateTime::now(&zone).ok()?;
+        i32::try_from(now.utc_offset().as_seconds()).ok()
+    }
+
+    /// `UTC+05:30` for 19800 seconds, `UTC` for none
+    fn format_utc_offset(seconds: i32) -> String {
+        if seconds == 0 {
+            return String::from("UTC");
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let minutes = seconds.unsigned_abs() / 60;
+        format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+
+    /// `Asia/Abu Dhabi (UTC+04:00)` in the `Asia` group for `Asia/Abu_Dhabi`
+    fn timezone_entry(tz: &str, offset: Option<i32>) -> LanguageRegionEntry {
+        let name: String = tz.chars().map(|c| if c == '_' { ' ' } else { c }).collect();
+        let display = match offset {
+            Some(offset) => format!("{name} ({})", Self::format_utc_offset(offset)),
+            None => name,
+        };
+        let group = tz
+            .split_once('/')
+            .map_or(OTHER_TIMEZONES, |(continent, _)| continent);
+        LanguageRegionEntry {
+            code: tz.to_owned(),
+            display,
+            group: group.to_owned(),
+            suggested: false,
+        }
+    }
+
+    /// Timezones with their current offset, those at the offset of `current` being suggested
+    async fn get_timezones(
+        timedate1: &Timedate1Proxy<'_>,
+        current: Option<&str>,
+    ) -> Vec<LanguageRegionEntry> {
+        let timezones = match timedate1.list_timezones().await {
+            Ok(timezones) => timezones,
+            Err(e) => {
+                warn!("Getting timezones failed: {e}");
+                return Vec::new();
+            }
+        };
+        let offsets: BTreeMap<&str, Option<i32>> = timezones
+            .iter()
+            .map(|tz| (tz.as_str(), Self::utc_offset(tz)))
+            .collect();
+        let mut entries: Vec<_> = timezones
+            .iter()
+            .map(|tz| Self::timezone_entry(tz, offsets[tz.as_str()]))
+            .collect();
+        if let Some(current) = current {
+            let offset = offsets
+                .get(current)
+                .copied()
+                .unwrap_or_else(|| Self::utc_offset(current));
+            let related = |tz: &str| offset.is_some() && offsets.get(tz) == Some(&offset);
+            Self::suggest(&mut entries, current, related);
+        }
+        entries
+    }
+
+    pub async fn get_current_settings() -> RegionSettings {
+        match Self::connect().await {
+            Ok((locale1, timedate1)) => RegionSettings {
+                locale: Self::get_current_locale(&locale1).await,
+                keyboard: Self::get_current_keyboard(&locale1).await,
+                timezone: Self::get_current_timezone(&timedate1).await,
+            },
+            Err(e) => {
+                warn!("Cannot reach localed and timedated: {e}");
+                RegionSettings::default()
+            }
+        }
     }
 
     pub async fn get_timezone_locale_info() -> LanguageRegionData {
         let (tx_lang, rx_lang) = async_channel::bounded(1);
-        let (tx_tz, rx_tz) = async_channel::bounded(1);
+        let (tx_keyboard, rx_keyboard) = async_channel::bounded(1);
         std::thread::spawn(move || {
-            if let Err(e) = (|| -> Result<(), Box<dyn std::error::Error>> {
-                let current = match Self::get_current_locale() {
-                    Ok(v) => Some(v),
-                    Err(e) => {
-                        warn!("Error detecting current locale: {e}");
-                        Some(String::from("en_US.utf8"))
-                    }
-                };
-                let locales = Self::get_locales()?;
-                Ok(tx_lang.send_blocking((current, locales))?)
-            })() {
-                warn!("Getting locales failed: {e}, using defaults");
-                drop(tx_lang);
+            match Self::get_locales() {
+                Ok(locales) => {
+                    let _ = tx_lang.send_blocking(locales);
+                }
+                Err(e) => warn!("Getting locales failed: {e}"),
             }
-
-            if let Err(e) = (|| -> Result<(), Box<dyn std::error::Error>> {
-                let current = match Self::get_current_timezone() {
-                    Ok(v) => Some(v),
-                    Err(e) => {
-                        warn!("Error detecting current timezone: {e}");
-                        Some(String::from("UTC"))
-                    }
-                };
-                let timezones = Self::get_timezones()?;
-                Ok(tx_tz.send_blocking((current, timezones))?)
-            })() {
-                warn!("Getting timezones failed: {e}, using defaults");
+            match Self::get_keyboard_layouts() {
+                Ok(keyboard) => {
+                    let _ = tx_keyboard.send_blocking(keyboard);
+                }
+                Err(e) => warn!("Getting keyboard layouts from {XKB_RULES_FILE} failed: {e}"),
             }
         });
 
-        let (current_language, languages) = rx_lang.recv().await.unwrap_or_else(|_| {
-            (
-                Some(String::from("en_US.utf8")),
-                vec![
-                    ("ar_AE.utf8", "Arabic (UAE)").into(),
-                    ("en_US.utf8", "English (United States)").into(),
-                ],
-            )
-        });
+        let (current_language, current_keyboard, current_timezone, mut timezones) =
+            match Self::connect().await {
+                Ok((locale1, timedate1)) => {
+                    let current_timezone = Self::get_current_timezone(&timedate1).await;
+                    let timezones =
+                        Self::get_timezones(&timedate1, current_timezone.as_deref()).await;
+                    (
+                        Self::get_current_locale(&locale1).await,
+                        Self::get_current_keyboard(&locale1).await,
+                        current_timezone,
+                        timezones,
+                    )
+                }
+                Err(e) => {
+                    warn!("Cannot reach localed and timedated: {e}");
+                    (None, None, None, Vec::new())
+                }
+            };
 
-        let (current_timezone, timezones) = rx_tz.recv().await.unwrap_or_else(|_| {
-            (
-                Some(String::from("UTC")),
-                vec![
-                    ("Europe/Helsinki", "Europe/Helsinki").into(),
-                    ("Asia/Abu_Dhabi", "Asia/Abu Dhabi").into(),
-                ],
-            )
-        });
+        // Without a list, at least the current settings can be reapplied
+        let current_language = current_language.or_else(|| Some(String::from("en_US.utf8")));
+        let mut languages = rx_lang
+            .recv()
+            .await
+            .unwrap_or_else(|_| current_language.iter().map(|l| (l, l).into()).collect());
+        Self::suggest_locales(&mut languages, current_language.as_deref());
+        let keyboard_layouts = rx_keyboard
+            .recv()
+            .await
+            .unwrap_or_else(|_| KeyboardLayouts {
+                layouts: vec![("ara", "Arabic").into(), ("us", "English (US)").into()],
+                variants: BTreeMap::new(),
+            });
+        let current_timezone = current_timezone.or_else(|| Some(String::from("UTC")));
+        if timezones.is_empty() {
+            timezones.extend(
+                current_timezone
+                    .iter()
+                    .map(|tz| Self::timezone_entry(tz, Self::utc_offset(tz))),
+            );
+        }
 
         LanguageRegionData {
             languages,
             current_language,
+            current_keyboard,
+            keyboard_layouts,
             timezones,
             current_timezone,
         }
     }
+
+    /// Calls `f` with the current locale, keyboard layout, timezone and clock status, then whenever
+    /// `PropertiesChanged` reports a change of them
+    pub async fn watch(f: impl Fn(RegionChange)) -> zbus::Result<()> {
+        let (locale1, timedate1) = Self::connect().await?;
+        let locale1 = &locale1;
+
+        let locales = locale1
+            .receive_locale_changed()
+            .await
+            .filter_map(|change| async move {
+                let assignments = change.get().await.ok()?;
+                Self::lang_of(&assignments).map(RegionChange::Locale)
+            });
+        let keyboard = |_| async move {
+            Self::get_current_keyboard(locale1)
+                .await
+                .map(RegionChange::Keyboard)
+        };
+        let layouts = locale1
+            .receive_x11_layout_changed()
+            .await
+            .filter_map(keyboard);
+        let variants = locale1
+            .receive_x11_variant_changed()
+            .await
+            .filter_map(keyboard);
+        let keymaps = locale1
+            .receive_vconsole_keymap_changed()
+            .await
+            .filter_map(keyboard);
+        let timezones =
+            timedate1
+                .receive_timezone_changed()
+                .await
+                .filter_map(|change| async move {
+                    let timezone = change.get().await.ok()?;
+                    Some(RegionChange::Timezone(timezone))
+                });
+        let timedate1 = &timedate1;
+        let clock = |_| async move {
+            match Self::read_clock_status(timedate1).await {
+                Ok(status) => Some(RegionChange::Clock(status)),
+                Err(e) => {
+                    warn!("Error reading the clock status: {e}");
+                    None
+                }
+            }
+        };
+        let ntp = timedate1.receive_ntp_changed().await.filter_map(clock);
+        let synchronized = timedate1
+            .receive_ntp_synchronized_changed()
+            .await
+            .filter_map(clock);
+
+        let mut changes = stream::select_all([
+            locales.boxed_local(),
+            layouts.boxed_local(),
+            variants.boxed_local(),
+            keymaps.boxed_local(),
+            timezones.boxed_local(),
+            ntp.boxed_local(),
+            synchronized.boxed_local(),
+        ]);
+        while let Some(change) = changes.next().await {
+            f(change);
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone)]
 pub struct LanguageRegionEntry {
     pub code: String,
     pub display: String,
+    /// Heading the entry is listed under, the language of a locale or the continent of a timezone
+    pub group: String,
+    /// Also listed at the top, being close to the current setting
+    pub suggested: bool,
 }
 
 impl<T: Into<String>, U: Into<String>> From<(T, U)> for LanguageRegionEntry {
@@ -205,19 +592,26 @@ impl<T: Into<String>, U: Into<String>> From<(T, U)> for LanguageRegionEntry {
         Self {
             code: val.0.into(),
             display: val.1.into(),
+            group: String::new(),
+            suggested: false,
         }
     }
 }
 
 impl From<LanguageRegionEntry> for DataGObject {
     fn from(val: LanguageRegionEntry) -> Self {
-        Self::new(val.code, val.display)
+        let obj = Self::new(val.code, val.display);
+        obj.set_group(val.group);
+        obj
     }
 }
 
 pub struct LanguageRegionData {
     pub languages: Vec<LanguageRegionEntry>,
     pub current_language: Option<String>,
+    /// X11 layout or console keymap
+    pub current_keyboard: Option<KeyboardLayout>,
+    pub keyboard_layouts: KeyboardLayouts,
     pub timezones: Vec<LanguageRegionEntry>,
     pub current_timezone: Option<String>,
 }
@@ -1,13 +1,37 @@
 mod application;
 mod about;
+mod activity_log;
+mod activity_page;
+mod app_catalog;
+mod audio_backend;
+mod audio_settings_page;
+mod cert_monitor;
+mod certificate;
+mod cli;
+mod config;
 mod connection_config;
 mod control_action;
 mod data_gobject;
+mod date_time_settings_page;
+mod dbus_service;
+mod deep_link;
+mod display_backend;
+mod display_settings_page;
+mod endpoint;
 mod error_popup;
+mod file_watcher;
 mod language_region_notify_popup;
 mod language_region_settings_page;
+mod launch_dialog;
+mod launch_history;
+mod launcher_page;
 mod locale_provider;
+mod network_backend;
+mod network_settings_page;
+mod notifications;
 mod plot;
+mod power_action;
+mod power_settings_page;
 mod prelude;
 mod security_icon;
 mod serie;
@@ -15,77 +39,179 @@ mod service_gobject;
 mod service_model;
 mod service_row;
 mod service_settings;
+mod session;
 mod settings;
 mod settings_action;
 mod status_icon;
 mod typed_list_store;
+mod update_backend;
+mod updater;
+mod updates_page;
+mod vm_capabilities;
 mod window;
-mod wireguard_vms;
+mod xdg;
 
-use self::application::ControlPanelGuiApplication;
-use self::window::ControlPanelGuiWindow;
+use self::application::{APP_ID, ControlPanelGuiApplication};
+use self::cli::Command;
+use self::config::{Config, LogConfig};
+use self::deep_link::DeepLink;
+use self::endpoint::Endpoint;
+use self::window::{ControlPanelGuiWindow, Page};
 use clap::{Parser, ValueEnum};
+use serde::Deserialize;
 use std::path::PathBuf;
 
-use givc_client::endpoint::TlsConfig;
 use gtk::gio;
 use gtk::prelude::*;
 use syslog::{BasicLogger, Formatter3164};
 
-use crate::wireguard_vms::initialize_wvm_list;
 use env_logger::Builder;
 use prelude::*;
 
-const ADMIN_SERVICE_ADDR: &str = "192.168.101.10";
-const ADMIN_SERVICE_PORT: u16 = 9001;
-
-#[derive(ValueEnum, Default, Debug, Clone, Copy, PartialEq)]
+#[derive(ValueEnum, Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum LogOutput {
     #[default]
     Syslog,
     Stdout,
 }
 
+/// Command line options, each of them overrides the matching configuration file entry
 #[derive(Parser, Debug)]
 #[command(name = "ctrl-panel")]
 #[command(about = "Ghaf Control Panel", long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Print machine readable JSON from subcommands
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// ghaf-panel:// link to open, e.g. ghaf-panel://settings/about
+    #[arg(value_name = "URI")]
+    link: Option<DeepLink>,
+
+    /// Page to show: services, launcher, activity, settings, settings/locale,
+    /// settings/datetime, settings/display, settings/audio, settings/network, settings/power,
+    /// settings/updates or settings/about
+    #[arg(long)]
+    page: Option<Page>,
+
+    /// Unit or VM to select on the services page
+    #[arg(long, conflicts_with = "page")]
+    select: Option<String>,
+
+    /// Connection profile to start with, see `[profiles]` in the configuration file
+    #[arg(long)]
+    profile: Option<String>,
+
     #[arg(long)]
     addr: Option<String>,
     #[arg(long)]
     port: Option<u16>,
 
-    #[arg(long, env = "NAME", default_value = "admin-vm")]
-    name: String, // for TLS service name
+    /// Admin service endpoint: tcp://host:port, unix:///path or vsock://cid:port
+    #[arg(long, conflicts_with_all = ["addr", "port"])]
+    endpoint: Option<Endpoint>,
+
+    #[arg(long, env = "NAME")]
+    name: Option<String>, // for TLS service name
 
-    #[arg(long, env = "CA_CERT", default_value = "/run/givc/ca-cert.pem")]
+    #[arg(long, env = "CA_CERT")]
     cacert: Option<PathBuf>,
 
-    #[arg(long, env = "HOST_CERT", default_value = "/run/givc/cert.pem")]
+    #[arg(long, env = "HOST_CERT")]
     cert: Option<PathBuf>,
 
-    #[arg(long, env = "HOST_KEY", default_value = "/run/givc/key.pem")]
+    #[arg(long, env = "HOST_KEY")]
     key: Option<PathBuf>,
 
-    #[arg(long, default_value = "/etc/ctrl-panel/wireguard-gui-vms.txt")]
-    wireguardlist: Option<PathBuf>,
+    /// Per-VM capabilities file, the legacy WireGuard VM list is accepted as well
+    #[arg(long, alias = "wireguardlist")]
+    capabilities: Option<PathBuf>,
+
+    /// TOML file listing launchable applications per VM
+    #[arg(long)]
+    app_catalog: Option<PathBuf>,
 
     #[arg(long, default_value_t)]
     notls: bool,
 
     /// Log severity
-    #[arg(long, default_value_t = log::Level::Info)]
-    pub log_level: log::Level,
+    #[arg(long)]
+    pub log_level: Option<log::Level>,
 
     /// Log output
-    #[arg(long, value_enum, default_value_t)]
-    pub log_output: LogOutput,
+    #[arg(long, value_enum)]
+    pub log_output: Option<LogOutput>,
+}
+
+impl Args {
+    /// Links to open in the running instance, `--page` and `--select` are links too
+    fn links(&mut self) -> Vec<DeepLink> {
+        let page = self.page.take().map(DeepLink::Page);
+        let unit = self.select.take().map(DeepLink::Unit);
+        self.link
+            .take()
+            .into_iter()
+            .chain(page)
+            .chain(unit)
+            .collect()
+    }
+
+    fn apply(self, config: &mut Config) {
+        if let Some(profile) = self.profile {
+            config.default_profile = Some(profile);
+        }
+        let (admin, tls) = config.startup_connection_mut();
+        if self.addr.is_some() || self.port.is_some() {
+            // --addr and --port describe a TCP endpoint, replacing a configured URI
+            let (addr, port) = match admin.primary() {
+                Endpoint::Tcp { addr, port } => (addr, port),
+                _ => (admin.addr.clone(), admin.port),
+            };
+            admin.addr = self.addr.unwrap_or(addr);
+            admin.port = self.port.unwrap_or(port);
+            admin.endpoint = None;
+        }
+        if let Some(endpoint) = self.endpoint {
+            admin.endpoint = Some(endpoint);
+        }
+        if self.notls {
+            tls.enable = false;
+        }
+        if let Some(name) = self.name {
+            tls.name = name;
+        }
+        if let Some(cacert) = self.cacert {
+            tls.ca_cert = cacert;
+        }
+        if let Some(cert) = self.cert {
+            tls.cert = cert;
+        }
+        if let Some(key) = self.key {
+            tls.key = key;
+        }
+        if let Some(capabilities) = self.capabilities {
+            config.files.capabilities = Some(capabilities);
+        }
+        if let Some(app_catalog) = self.app_catalog {
+            config.files.app_catalog = app_catalog;
+        }
+        if let Some(level) = self.log_level {
+            config.log.level = level;
+        }
+        if let Some(output) = self.log_output {
+            config.log.output = output;
+        }
+    }
 }
 
-fn initialize_logger(args: &Args) {
+fn initialize_logger(config: &LogConfig) {
     // Initialize env_logger
-    let log_level = args.log_level.to_level_filter();
-    match args.log_output {
+    let log_level = config.level.to_level_filter();
+    match config.output {
         LogOutput::Stdout => {
             // You can set the level in code here
             Builder::new()
@@ -119,37 +245,28 @@ fn main() /*-> glib::ExitCode*/
 {
     //std::env::set_var("RUST_BACKTRACE", "full");
     // Parse the command-line arguments
-    let args = Args::parse();
-    initialize_logger(&args);
-
-    let addr = if let Some(addr) = args.addr {
-        addr
-    } else {
-        String::from(ADMIN_SERVICE_ADDR)
-    };
-
-    let port = if let Some(port) = args.port {
-        port
-    } else {
-        ADMIN_SERVICE_PORT
-    };
-
-    let tls_info = if args.notls {
-        None
-    } else {
-        Some((
-            args.name.clone(),
-            TlsConfig {
-                ca_cert_file_path: args.cacert.expect("cacert is required"),
-                cert_file_path: args.cert.expect("cert is required"),
-                key_file_path: args.key.expect("key is required"),
-                tls_name: Some(args.name),
-            },
-        ))
+    let mut args = Args::parse();
+    let command = args.command.take();
+    let json = args.json;
+    let links = args.links();
+    let config = Config::load().and_then(|mut config| {
+        args.apply(&mut config);
+        config.validate()?;
+        Ok(config)
+    });
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("ctrl-panel: {e}");
+            std::process::exit(2);
+        }
     };
+    initialize_logger(&config.log);
 
-    //read file with wireguard VMs
-    initialize_wvm_list(&args.wireguardlist.expect("wireguard vm list file required"));
+    // Subcommands talk to the admin service directly, without any window
+    if let Some(command) = command {
+        std::process::exit(cli::run(command, &config, json));
+    }
 
     // Load resources
     gio::resources_register_include!("control_panel_gui.gresource")
@@ -158,18 +275,16 @@ fn main() /*-> glib::ExitCode*/
     // Create a new GtkApplication. The application manages our main loop,
     // application windows, integration with the window manager/compositor, and
     // desktop features such as file opening and single-instance applications.
-    let app = ControlPanelGuiApplication::new(
-        "ae.tii.ghaf./controlpanelgui",
-        gio::ApplicationFlags::empty(),
-        addr,
-        port,
-        tls_info,
-    );
+    let app = ControlPanelGuiApplication::new(APP_ID, gio::ApplicationFlags::HANDLES_OPEN, config);
 
     // Run the application. This function will block until the application
     // exits. Upon return, we have our exit code to return to the shell. (This
     // is the code you see when you do `echo $?` after running a command in a
     // terminal.
-    //put empty array as args, cause we need our ones to be processed
-    app.run_with_args(&[""; 0]);
+    // Our own options are processed already, only links are passed on. GApplication
+    // forwards them to the primary instance, which receives them in `open`.
+    let argv: Vec<String> = std::iter::once("ctrl-panel".to_owned())
+        .chain(links.iter().map(ToString::to_string))
+        .collect();
+    app.run_with_args(&argv);
 }
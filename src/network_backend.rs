@@ -0,0 +1,891 @@
+use futures::future::LocalBoxFuture;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use thiserror::Error as ThisError;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, proxy};
+
+use crate::prelude::*;
+
+/// `NM_DEVICE_TYPE_*` of the devices the page tells apart
+const NM_DEVICE_TYPE_ETHERNET: u32 = 1;
+const NM_DEVICE_TYPE_WIFI: u32 = 2;
+const NM_DEVICE_TYPE_LOOPBACK: u32 = 32;
+/// `NM_802_11_AP_FLAGS_PRIVACY`, set for WEP networks which have no WPA flags
+const NM_AP_FLAGS_PRIVACY: u32 = 0x1;
+/// `NM_802_11_AP_SEC_KEY_MGMT_*` of the WPA and RSN flags
+const NM_AP_SEC_KEY_MGMT_PSK: u32 = 0x100;
+const NM_AP_SEC_KEY_MGMT_SAE: u32 = 0x400;
+
+/// Connection settings by setting name, e.g. `802-11-wireless`
+type ConnectionSettings<'a> = HashMap<String, HashMap<String, Value<'a>>>;
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager",
+    gen_blocking = false
+)]
+trait NetworkManager {
+    fn get_devices(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    fn activate_connection(
+        &self,
+        connection: &ObjectPath<'_>,
+        device: &ObjectPath<'_>,
+        specific_object: &ObjectPath<'_>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    fn add_and_activate_connection(
+        &self,
+        connection: ConnectionSettings<'_>,
+        device: &ObjectPath<'_>,
+        specific_object: &ObjectPath<'_>,
+    ) -> zbus::Result<(OwnedObjectPath, OwnedObjectPath)>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Settings",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/Settings",
+    gen_blocking = false
+)]
+trait Settings {
+    fn list_connections(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Settings.Connection",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait SettingsConnection {
+    /// Without secrets
+    fn get_settings(&self) -> zbus::Result<HashMap<String, HashMap<String, OwnedValue>>>;
+
+    fn update(&self, properties: ConnectionSettings<'_>) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Device",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait Device {
+    fn disconnect(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn interface(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn device_type(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn hw_address(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn ip4_config(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn active_connection(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Device.Wireless",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait Wireless {
+    fn get_all_access_points(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    fn request_scan(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn active_access_point(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.AccessPoint",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait AccessPoint {
+    #[zbus(property)]
+    fn ssid(&self) -> zbus::Result<Vec<u8>>;
+
+    #[zbus(property)]
+    fn strength(&self) -> zbus::Result<u8>;
+
+    #[zbus(property)]
+    fn flags(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn wpa_flags(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn rsn_flags(&self) -> zbus::Result<u32>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.IP4Config",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait Ip4Config {
+    #[zbus(property)]
+    fn address_data(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+
+    #[zbus(property)]
+    fn gateway(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn nameserver_data(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Connection.Active",
+    default_service = "org.freedesktop.NetworkManager",
+    gen_blocking = false
+)]
+trait ActiveConnection {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Other,
+}
+
+impl fmt::Display for InterfaceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ethernet => "Ethernet",
+            Self::Wifi => "Wi-Fi",
+            Self::Other => "Other",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceState {
+    Unmanaged,
+    Unavailable,
+    Disconnected,
+    Connecting,
+    Connected,
+    Disconnecting,
+    Failed,
+}
+
+impl InterfaceState {
+    /// From `NMDeviceState`
+    fn from_nm(state: u32) -> Self {
+        match state {
+            20 => Self::Unavailable,
+            30 => Self::Disconnected,
+            40..=90 => Self::Connecting,
+            100 => Self::Connected,
+            110 => Self::Disconnecting,
+            120 => Self::Failed,
+            _ => Self::Unmanaged,
+        }
+    }
+}
+
+impl fmt::Display for InterfaceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unmanaged => "Not managed",
+            Self::Unavailable => "Unavailable",
+            Self::Disconnected => "Disconnected",
+            Self::Connecting => "Connecting…",
+            Self::Connected => "Connected",
+            Self::Disconnecting => "Disconnecting…",
+            Self::Failed => "Failed",
+        })
+    }
+}
+
+/// IPv4 configuration of a connected interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpConfig {
+    /// With their prefix, e.g. `192.168.1.5/24`
+    pub addresses: Vec<String>,
+    pub gateway: Option<String>,
+    pub dns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    /// Kernel name, e.g. `wlp0s5f0`
+    pub name: String,
+    pub kind: InterfaceKind,
+    pub state: InterfaceState,
+    pub hw_address: String,
+    /// Name of the active connection profile
+    pub connection: Option<String>,
+    pub ip: Option<IpConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiNetwork {
+    pub ssid: String,
+    /// Signal quality in percent
+    pub strength: u8,
+    pub secured: bool,
+    /// Joined by the interface
+    pub active: bool,
+}
+
+/// Key management of a Wi-Fi network, told from the flags of its access point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WifiSecurity {
+    Open,
+    Wep,
+    WpaPsk,
+    /// WPA3 Personal only
+    Sae,
+}
+
+impl WifiSecurity {
+    fn from_flags(flags: u32, wpa_flags: u32, rsn_flags: u32) -> Self {
+        if wpa_flags == 0 && rsn_flags == 0 {
+            if flags & NM_AP_FLAGS_PRIVACY == 0 {
+                Self::Open
+            } else {
+                Self::Wep
+            }
+        } else if rsn_flags & NM_AP_SEC_KEY_MGMT_SAE != 0
+            && (rsn_flags | wpa_flags) & NM_AP_SEC_KEY_MGMT_PSK == 0
+        {
+            Self::Sae
+        } else {
+            Self::WpaPsk
+        }
+    }
+
+    /// `802-11-wireless-security` setting with `password`, none for open networks
+    fn setting(self, password: &str) -> Option<HashMap<String, Value<'_>>> {
+        let (key_mgmt, password_key) = match self {
+            Self::Open => return None,
+            Self::Wep => ("none", "wep-key0"),
+            Self::WpaPsk => ("wpa-psk", "psk"),
+            Self::Sae => ("sae", "psk"),
+        };
+        Some(HashMap::from([
+            ("key-mgmt".into(), Value::from(key_mgmt)),
+            (password_key.into(), Value::from(password)),
+        ]))
+    }
+}
+
+/// Access point found by the last scan
+struct VisibleAccessPoint {
+    network: WifiNetwork,
+    security: WifiSecurity,
+    path: OwnedObjectPath,
+}
+
+/// What the Network page shows, networks are those of the first Wi-Fi interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkState {
+    pub interfaces: Vec<Interface>,
+    pub networks: Vec<WifiNetwork>,
+}
+
+impl NetworkState {
+    pub fn wifi_interface(&self) -> Option<&Interface> {
+        self.interfaces
+            .iter()
+            .find(|interface| interface.kind == InterfaceKind::Wifi)
+    }
+}
+
+#[derive(ThisError, Debug)]
+pub enum NetworkError {
+    #[error("unknown interface {0}")]
+    UnknownInterface(String),
+    #[error("{0} is not a Wi-Fi interface")]
+    NotWifi(String),
+    #[error("network \"{0}\" is out of range")]
+    UnknownNetwork(String),
+    #[error("network \"{0}\" needs a password")]
+    PasswordRequired(String),
+    #[error(transparent)]
+    DBus(#[from] zbus::Error),
+}
+
+/// Interfaces and Wi-Fi networks of the net-vm, or an in-memory stand-in for development
+pub trait NetworkBackend: fmt::Debug {
+    fn interfaces(&self) -> LocalBoxFuture<'_, Result<Vec<Interface>, NetworkError>>;
+
+    fn state(&self) -> LocalBoxFuture<'_, Result<NetworkState, NetworkError>> {
+        Box::pin(async move {
+            let mut state = NetworkState {
+                interfaces: self.interfaces().await?,
+                networks: Vec::new(),
+            };
+            if let Some(wifi) = state.wifi_interface().map(|wifi| wifi.name.clone()) {
+                state.networks = self.networks(&wifi).await?;
+            }
+            Ok(state)
+        })
+    }
+
+    /// Networks found by the last scan of `interface`, strongest first
+    fn networks<'a>(
+        &'a self,
+        interface: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Vec<WifiNetwork>, NetworkError>>;
+
+    /// Asks for a new scan, its results come a few seconds later
+    fn scan<'a>(&'a self, interface: &'a str) -> LocalBoxFuture<'a, Result<(), NetworkError>>;
+
+    /// Joins `ssid`, `password` is empty for open networks
+    fn connect_wifi<'a>(
+        &'a self,
+        interface: &'a str,
+        ssid: &'a str,
+        password: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), NetworkError>>;
+
+    fn disconnect<'a>(&'a self, interface: &'a str)
+    -> LocalBoxFuture<'a, Result<(), NetworkError>>;
+}
+
+/// Strongest first, access points of the same network are listed once with the strongest signal
+fn sort_networks(networks: &mut Vec<WifiNetwork>) {
+    let active: HashSet<String> = networks
+        .iter()
+        .filter(|network| network.active)
+        .map(|network| network.ssid.clone())
+        .collect();
+    networks.sort_by(|a, b| {
+        b.strength
+            .cmp(&a.strength)
+            .then_with(|| a.ssid.cmp(&b.ssid))
+    });
+    let mut seen = HashSet::new();
+    networks.retain(|network| seen.insert(network.ssid.clone()));
+    for network in networks.iter_mut() {
+        network.active = active.contains(&network.ssid);
+    }
+}
+
+/// NetworkManager on a D-Bus connection, the system bus by default. On Ghaf the admin service
+/// forwards the system bus of the net-vm to the GUI VM as a socket, whose address is given
+/// to reach the NetworkManager of the net-vm.
+#[derive(Debug, Default)]
+pub struct NetworkManagerBackend {
+    /// D-Bus address, e.g. `unix:path=/tmp/dbusproxy_net.sock`
+    address: Option<String>,
+    connection: RefCell<Option<Connection>>,
+}
+
+impl NetworkManagerBackend {
+    pub fn new(address: Option<String>) -> Self {
+        Self {
+            address,
+            connection: RefCell::default(),
+        }
+    }
+
+    /// Runs `call`, forgetting the connection when it broke so that the next call connects
+    /// again, e.g. after the net-vm restarted
+    async fn call<T>(
+        &self,
+        call: impl Future<Output = Result<T, NetworkError>>,
+    ) -> Result<T, NetworkError> {
+        let result = call.await;
+        if let Err(NetworkError::DBus(
+            e @ (zbus::Error::InputOutput(_) | zbus::Error::Handshake(_)),
+        )) = &result
+        {
+            warn!("Connection to NetworkManager lost: {e}");
+            self.connection.take();
+        }
+        result
+    }
+
+    /// Connects on first use, the net-vm may start after the panel
+    async fn connection(&self) -> zbus::Result<Connection> {
+        if let Some(connection) = self.connection.borrow().clone() {
+            return Ok(connection);
+        }
+        let connection = match &self.address {
+            Some(address) => {
+                zbus::connection::Builder::address(address.as_str())?
+                    .build()
+                    .await?
+            }
+            None => Connection::system().await?,
+        };
+        self.connection.replace(Some(connection.clone()));
+        Ok(connection)
+    }
+
+    async fn device(
+        connection: &Connection,
+        path: OwnedObjectPath,
+    ) -> zbus::Result<DeviceProxy<'static>> {
+        DeviceProxy::builder(connection).path(path)?.build().await
+    }
+
+    /// Device and Wireless proxies of the interface named `interface`
+    async fn find_device(
+        &self,
+        interface: &str,
+    ) -> Result<(DeviceProxy<'static>, OwnedObjectPath), NetworkError> {
+        let connection = self.connection().await?;
+        let network_manager = NetworkManagerProxy::new(&connection).await?;
+        for path in network_manager.get_devices().await? {
+            let device = Self::device(&connection, path.clone()).await?;
+            if device.interface().await? == interface {
+                return Ok((device, path));
+            }
+        }
+        Err(NetworkError::UnknownInterface(interface.into()))
+    }
+
+    async fn wireless(
+        &self,
+        interface: &str,
+    ) -> Result<(WirelessProxy<'static>, OwnedObjectPath), NetworkError> {
+        let (device, path) = self.find_device(interface).await?;
+        if device.device_type().await? != NM_DEVICE_TYPE_WIFI {
+            return Err(NetworkError::NotWifi(interface.into()));
+        }
+        let wireless = WirelessProxy::builder(&self.connection().await?)
+            .path(path.clone())?
+            .build()
+            .await?;
+        Ok((wireless, path))
+    }
+
+    async fn ip_config(connection: &Connection, path: OwnedObjectPath) -> zbus::Result<IpConfig> {
+        let config = Ip4ConfigProxy::builder(connection)
+            .path(path)?
+            .build()
+            .await?;
+        let text = |entry: &HashMap<String, OwnedValue>, key: &str| {
+            entry
+                .get(key)
+                .and_then(|value| value.downcast_ref::<String>().ok())
+        };
+        let addresses = config
+            .address_data()
+            .await?
+            .iter()
+            .filter_map(|entry| {
+                let address = text(entry, "address")?;
+                let prefix = entry.get("prefix")?.downcast_ref::<u32>().ok()?;
+                Some(format!("{address}/{prefix}"))
+            })
+            .collect();
+        let gateway = config.gateway().await?;
+        let dns = config
+            .nameserver_data()
+            .await?
+            .iter()
+            .filter_map(|entry| text(entry, "address"))
+            .collect();
+        Ok(IpConfig {
+            addresses,
+            gateway: (!gateway.is_empty()).then_some(gateway),
+            dns,
+        })
+    }
+
+    async fn interface(
+        connection: &Connection,
+        device: &DeviceProxy<'_>,
+    ) -> zbus::Result<Interface> {
+        let state = InterfaceState::from_nm(device.state().await?);
+        let kind = match device.device_type().await? {
+            NM_DEVICE_TYPE_ETHERNET => InterfaceKind::Ethernet,
+            NM_DEVICE_TYPE_WIFI => InterfaceKind::Wifi,
+            _ => InterfaceKind::Other,
+        };
+        let active = device.active_connection().await?;
+        let connection_name = if active.as_str() == "/" {
+            None
+        } else {
+            let active = ActiveConnectionProxy::builder(connection)
+                .path(active)?
+                .build()
+                .await?;
+            Some(active.id().await?)
+        };
+        let ip4_config = device.ip4_config().await?;
+        let ip = if state == InterfaceState::Connected && ip4_config.as_str() != "/" {
+            Some(Self::ip_config(connection, ip4_config).await?)
+        } else {
+            None
+        };
+        Ok(Interface {
+            name: device.interface().await?,
+            kind,
+            state,
+            hw_address: device.hw_address().await.unwrap_or_default(),
+            connection: connection_name,
+            ip,
+        })
+    }
+
+    async fn access_point(
+        connection: &Connection,
+        path: OwnedObjectPath,
+    ) -> zbus::Result<VisibleAccessPoint> {
+        let access_point = AccessPointProxy::builder(connection)
+            .path(path.clone())?
+            .build()
+            .await?;
+        let security = WifiSecurity::from_flags(
+            access_point.flags().await?,
+            access_point.wpa_flags().await?,
+            access_point.rsn_flags().await?,
+        );
+        let network = WifiNetwork {
+            ssid: String::from_utf8_lossy(&access_point.ssid().await?).into_owned(),
+            strength: access_point.strength().await?,
+            secured: security != WifiSecurity::Open,
+            active: false,
+        };
+        Ok(VisibleAccessPoint {
+            network,
+            security,
+            path,
+        })
+    }
+
+    /// Visible access points and the device path, hidden networks are left out
+    async fn access_points(
+        &self,
+        interface: &str,
+    ) -> Result<(Vec<VisibleAccessPoint>, OwnedObjectPath), NetworkError> {
+        let connection = self.connection().await?;
+        let (wireless, device) = self.wireless(interface).await?;
+        let active = wireless.active_access_point().await?;
+        let mut access_points = Vec::new();
+        for path in wireless.get_all_access_points().await? {
+            let is_active = path == active;
+            let mut access_point = Self::access_point(&connection, path).await?;
+            if access_point.network.ssid.is_empty() {
+                continue;
+            }
+            access_point.network.active = is_active;
+            access_points.push(access_point);
+        }
+        Ok((access_points, device))
+    }
+
+    /// Connection profile saved for the Wi-Fi network `ssid`, with its settings
+    async fn saved_connection<'a>(
+        connection: &Connection,
+        ssid: &str,
+    ) -> zbus::Result<Option<(SettingsConnectionProxy<'static>, ConnectionSettings<'a>)>> {
+        for path in SettingsProxy::new(connection)
+            .await?
+            .list_connections()
+            .await?
+        {
+            let saved = SettingsConnectionProxy::builder(connection)
+                .path(path)?
+                .build()
+                .await?;
+            let settings = saved.get_settings().await?;
+            let saved_ssid = settings
+                .get("802-11-wireless")
+                .and_then(|wireless| wireless.get("ssid"))
+                .and_then(|ssid| ssid.try_clone().ok())
+                .and_then(|ssid| Vec::<u8>::try_from(ssid).ok());
+            if saved_ssid.as_deref() == Some(ssid.as_bytes()) {
+                let settings = settings
+                    .into_iter()
+                    .map(|(name, setting)| {
+                        let setting = setting
+                            .into_iter()
+                            .map(|(key, value)| (key, value.into()))
+                            .collect();
+                        (name, setting)
+                    })
+                    .collect();
+                return Ok(Some((saved, settings)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl NetworkBackend for NetworkManagerBackend {
+    fn interfaces(&self) -> LocalBoxFuture<'_, Result<Vec<Interface>, NetworkError>> {
+        Box::pin(self.call(async move {
+            let connection = self.connection().await?;
+            let network_manager = NetworkManagerProxy::new(&connection).await?;
+            let mut interfaces = Vec::new();
+            for path in network_manager.get_devices().await? {
+                let device = Self::device(&connection, path).await?;
+                if device.device_type().await? == NM_DEVICE_TYPE_LOOPBACK {
+                    continue;
+                }
+                interfaces.push(Self::interface(&connection, &device).await?);
+            }
+            Ok(interfaces)
+        }))
+    }
+
+    fn networks<'a>(
+        &'a self,
+        interface: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Vec<WifiNetwork>, NetworkError>> {
+        Box::pin(self.call(async move {
+            let (access_points, _) = self.access_points(interface).await?;
+            let mut networks: Vec<WifiNetwork> = access_points
+                .into_iter()
+                .map(|access_point| access_point.network)
+                .collect();
+            sort_networks(&mut networks);
+            Ok(networks)
+        }))
+    }
+
+    fn scan<'a>(&'a self, interface: &'a str) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(self.call(async move {
+            let (wireless, _) = self.wireless(interface).await?;
+            wireless.request_scan(HashMap::new()).await?;
+            Ok(())
+        }))
+    }
+
+    fn connect_wifi<'a>(
+        &'a self,
+        interface: &'a str,
+        ssid: &'a str,
+        password: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(self.call(async move {
+            let (access_points, device) = self.access_points(interface).await?;
+            let access_point = access_points
+                .into_iter()
+                .filter(|access_point| access_point.network.ssid == ssid)
+                .max_by_key(|access_point| access_point.network.strength)
+                .ok_or_else(|| NetworkError::UnknownNetwork(ssid.into()))?;
+
+            let connection = self.connection().await?;
+            let network_manager = NetworkManagerProxy::new(&connection).await?;
+            // Joining again reuses the saved profile instead of adding one more, with its
+            // password unless a new one is given
+            if let Some((saved, mut settings)) = Self::saved_connection(&connection, ssid).await? {
+                info!("Joining saved Wi-Fi network {ssid} on {interface}");
+                if let Some(security) = access_point
+                    .security
+                    .setting(password)
+                    .filter(|_| !password.is_empty())
+                {
+                    settings.insert("802-11-wireless-security".into(), security);
+                    saved.update(settings).await?;
+                }
+                network_manager
+                    .activate_connection(saved.inner().path(), &device, &access_point.path)
+                    .await?;
+                return Ok(());
+            }
+
+            if access_point.network.secured && password.is_empty() {
+                return Err(NetworkError::PasswordRequired(ssid.into()));
+            }
+            let mut settings = ConnectionSettings::new();
+            settings.insert(
+                "802-11-wireless".into(),
+                HashMap::from([("ssid".into(), Value::from(ssid.as_bytes()))]),
+            );
+            if let Some(security) = access_point.security.setting(password) {
+                settings.insert("802-11-wireless-security".into(), security);
+            }
+            info!("Joining Wi-Fi network {ssid} on {interface}");
+            network_manager
+                .add_and_activate_connection(settings, &device, &access_point.path)
+                .await?;
+            Ok(())
+        }))
+    }
+
+    fn disconnect<'a>(
+        &'a self,
+        interface: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(self.call(async move {
+            let (device, _) = self.find_device(interface).await?;
+            device.disconnect().await?;
+            Ok(())
+        }))
+    }
+}
+
+/// A wired and a Wi-Fi interface with a few networks kept in memory, for developing the Network
+/// page without a net-vm. Any password longer than 7 characters is accepted, networks joined
+/// before are joined again without one.
+#[derive(Debug)]
+pub struct FakeNetworkBackend {
+    interfaces: RefCell<Vec<Interface>>,
+    networks: RefCell<Vec<WifiNetwork>>,
+    saved: RefCell<HashSet<String>>,
+}
+
+impl Default for FakeNetworkBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeNetworkBackend {
+    const WIFI: &'static str = "wlp0s5f0";
+
+    pub fn new() -> Self {
+        let network = |ssid: &str, strength, secured| WifiNetwork {
+            ssid: ssid.into(),
+            strength,
+            secured,
+            active: false,
+        };
+        Self {
+            interfaces: RefCell::new(vec![
+                Interface {
+                    name: "ens4".into(),
+                    kind: InterfaceKind::Ethernet,
+                    state: InterfaceState::Connected,
+                    hw_address: "52:54:00:12:34:56".into(),
+                    connection: Some("Wired connection 1".into()),
+                    ip: Some(IpConfig {
+                        addresses: vec!["192.168.100.2/24".into()],
+                        gateway: Some("192.168.100.1".into()),
+                        dns: vec!["192.168.100.1".into()],
+                    }),
+                },
+                Interface {
+                    name: Self::WIFI.into(),
+                    kind: InterfaceKind::Wifi,
+                    state: InterfaceState::Disconnected,
+                    hw_address: "02:00:00:ab:cd:ef".into(),
+                    connection: None,
+                    ip: None,
+                },
+            ]),
+            networks: RefCell::new(vec![
+                network("Office", 82, true),
+                network("Guest", 64, false),
+                network("Lab 5G", 40, true),
+            ]),
+            saved: RefCell::default(),
+        }
+    }
+
+    fn wifi_interface(&self, interface: &str) -> Result<(), NetworkError> {
+        match self
+            .interfaces
+            .borrow()
+            .iter()
+            .find(|candidate| candidate.name == interface)
+        {
+            Some(candidate) if candidate.kind == InterfaceKind::Wifi => Ok(()),
+            Some(_) => Err(NetworkError::NotWifi(interface.into())),
+            None => Err(NetworkError::UnknownInterface(interface.into())),
+        }
+    }
+
+    fn set_connection(&self, interface: &str, network: Option<&str>) {
+        for candidate in self.interfaces.borrow_mut().iter_mut() {
+            if candidate.name != interface {
+                continue;
+            }
+            candidate.connection = network.map(Into::into);
+            candidate.state = if network.is_some() {
+                InterfaceState::Connected
+            } else {
+                InterfaceState::Disconnected
+            };
+            candidate.ip = network.map(|_| IpConfig {
+                addresses: vec!["10.20.0.17/24".into()],
+                gateway: Some("10.20.0.1".into()),
+                dns: vec!["10.20.0.1".into()],
+            });
+        }
+        if interface == Self::WIFI {
+            for candidate in self.networks.borrow_mut().iter_mut() {
+                candidate.active = Some(candidate.ssid.as_str()) == network;
+            }
+        }
+    }
+}
+
+impl NetworkBackend for FakeNetworkBackend {
+    fn interfaces(&self) -> LocalBoxFuture<'_, Result<Vec<Interface>, NetworkError>> {
+        Box::pin(async { Ok(self.interfaces.borrow().clone()) })
+    }
+
+    fn networks<'a>(
+        &'a self,
+        interface: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Vec<WifiNetwork>, NetworkError>> {
+        Box::pin(async move {
+            self.wifi_interface(interface)?;
+            let mut networks = self.networks.borrow().clone();
+            sort_networks(&mut networks);
+            Ok(networks)
+        })
+    }
+
+    fn scan<'a>(&'a self, interface: &'a str) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(async move { self.wifi_interface(interface) })
+    }
+
+    fn connect_wifi<'a>(
+        &'a self,
+        interface: &'a str,
+        ssid: &'a str,
+        password: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(async move {
+            self.wifi_interface(interface)?;
+            let secured = self
+                .networks
+                .borrow()
+                .iter()
+                .find(|network| network.ssid == ssid)
+                .map(|network| network.secured)
+                .ok_or_else(|| NetworkError::UnknownNetwork(ssid.into()))?;
+            let saved = self.saved.borrow().contains(ssid);
+            if secured && password.len() < 8 && !(saved && password.is_empty()) {
+                return Err(NetworkError::PasswordRequired(ssid.into()));
+            }
+            self.saved.borrow_mut().insert(ssid.into());
+            self.set_connection(interface, Some(ssid));
+            Ok(())
+        })
+    }
+
+    fn disconnect<'a>(
+        &'a self,
+        interface: &'a str,
+    ) -> LocalBoxFuture<'a, Result<(), NetworkError>> {
+        Box::pin(async move {
+            if !self
+                .interfaces
+                .borrow()
+                .iter()
+                .any(|candidate| candidate.name == interface)
+            {
+                return Err(NetworkError::UnknownInterface(interface.into()));
+            }
+            self.set_connection(interface, None);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use adw::prelude::*;
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::network_backend::{Interface, InterfaceState, NetworkState, WifiNetwork};
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{Box, Button, CompositeTemplate, Label, ListBox, Spinner, glib};
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use crate::network_backend::NetworkState;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/network_settings_page.ui")]
+    pub struct NetworkSettingsPage {
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub no_interfaces_label: TemplateChild<Label>,
+        #[template_child]
+        pub interfaces_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub wifi_box: TemplateChild<Box>,
+        #[template_child]
+        pub scan_button: TemplateChild<Button>,
+        #[template_child]
+        pub scan_spinner: TemplateChild<Spinner>,
+        #[template_child]
+        pub no_networks_label: TemplateChild<Label>,
+        #[template_child]
+        pub networks_list: TemplateChild<ListBox>,
+
+        pub(super) state: RefCell<NetworkState>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for NetworkSettingsPage {
+        const NAME: &'static str = "NetworkSettingsPage";
+        type Type = super::NetworkSettingsPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl NetworkSettingsPage {
+        #[template_callback]
+        fn on_scan_clicked(&self) {
+            let Some(interface) = self
+                .state
+                .borrow()
+                .wifi_interface()
+                .map(|interface| interface.name.clone())
+            else {
+                return;
+            };
+            self.obj().emit_by_name::<()>("scan", &[&interface]);
+        }
+    }
+
+    impl ObjectImpl for NetworkSettingsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            // Links and networks of the net-vm change on their own, so they are read whenever shown
+            self.obj()
+                .connect_map(|page| page.emit_by_name::<()>("refresh", &[]));
+            self.obj().set_state(NetworkState::default());
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("refresh").build(),
+                    // Wi-Fi interface
+                    Signal::builder("scan")
+                        .param_types([String::static_type()])
+                        .build(),
+                    // Wi-Fi interface, SSID, password (empty for open networks)
+                    Signal::builder("connect-wifi")
+                        .param_types([
+                            String::static_type(),
+                            String::static_type(),
+                            String::static_type(),
+                        ])
+                        .build(),
+                    // Interface
+                    Signal::builder("disconnect")
+                        .param_types([String::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for NetworkSettingsPage {}
+    impl BoxImpl for NetworkSettingsPage {}
+}
+
+glib::wrapper! {
+pub struct NetworkSettingsPage(ObjectSubclass<imp::NetworkSettingsPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for NetworkSettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkSettingsPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_state(&self, state: NetworkState) {
+        let imp = self.imp();
+        imp.interfaces_list.remove_all();
+        for interface in &state.interfaces {
+            imp.interfaces_list.append(&self.interface_row(interface));
+        }
+        imp.interfaces_list
+            .set_visible(!state.interfaces.is_empty());
+        imp.no_interfaces_label
+            .set_visible(state.interfaces.is_empty());
+
+        imp.networks_list.remove_all();
+        if let Some(wifi) = state.wifi_interface() {
+            for network in &state.networks {
+                imp.networks_list
+                    .append(&self.network_row(&wifi.name, network));
+            }
+        }
+        imp.wifi_box.set_visible(state.wifi_interface().is_some());
+        imp.networks_list.set_visible(!state.networks.is_empty());
+        imp.no_networks_label.set_visible(state.networks.is_empty());
+        imp.state.replace(state);
+    }
+
+    /// Shown above the interfaces until the next successful read
+    pub fn set_error(&self, error: Option<&str>) {
+        let label = &self.imp().status_label;
+        label.set_label(error.unwrap_or_default());
+        label.set_visible(error.is_some());
+    }
+
+    /// While a Wi-Fi scan runs
+    pub fn set_scanning(&self, scanning: bool) {
+        let imp = self.imp();
+        imp.scan_button.set_sensitive(!scanning);
+        imp.scan_spinner.set_visible(scanning);
+        imp.scan_spinner.set_spinning(scanning);
+    }
+
+    fn interface_row(&self, interface: &Interface) -> gtk::Box {
+        let row = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(10)
+            .margin_end(10)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+        let details = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(2)
+            .hexpand(true)
+            .build();
+        let title = match &interface.connection {
+            Some(connection) => format!("{} ({}) – {connection}", interface.name, interface.kind),
+            None => format!("{} ({})", interface.name, interface.kind),
+        };
+        details.append(&gtk::Label::builder().label(title).xalign(0.).build());
+
+        let mut lines = vec![interface.state.to_string()];
+        if let Some(ip) = &interface.ip {
+            if !ip.addresses.is_empty() {
+                lines.push(format!("Address: {}", ip.addresses.join(", ")));
+            }
+            if let Some(gateway) = &ip.gateway {
+                lines.push(format!("Gateway: {gateway}"));
+            }
+            if !ip.dns.is_empty() {
+                lines.push(format!("DNS: {}", ip.dns.join(", ")));
+            }
+        }
+        if !interface.hw_address.is_empty() {
+            lines.push(format!("Hardware address: {}", interface.hw_address));
+        }
+        let info = gtk::Label::builder()
+            .label(lines.join("\n"))
+            .xalign(0.)
+            .selectable(true)
+            .build();
+        info.add_css_class("dim-label");
+        details.append(&info);
+        row.append(&details);
+
+        if matches!(
+            interface.state,
+            InterfaceState::Connected | InterfaceState::Connecting
+        ) {
+            let button = gtk::Button::builder()
+                .label("Disconnect")
+                .valign(gtk::Align::Center)
+                .build();
+            let name = interface.name.clone();
+            button.connect_clicked(glib::clone!(
+                #[weak(rename_to = page)]
+                self,
+                move |_| page.emit_by_name::<()>("disconnect", &[&name])
+            ));
+            row.append(&button);
+        }
+        row
+    }
+
+    fn network_row(&self, interface: &str, network: &WifiNetwork) -> gtk::Box {
+        let row = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(10)
+            .margin_end(10)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+        let ssid = gtk::Label::builder()
+            .label(&network.ssid)
+            .xalign(0.)
+            .hexpand(true)
+            .build();
+        row.append(&ssid);
+        if network.secured {
+            row.append(
+                &gtk::Image::builder()
+                    .icon_name("network-wireless-encrypted-symbolic")
+                    .tooltip_text("Secured")
+                    .build(),
+            );
+        }
+        let strength = gtk::Label::builder()
+            .label(format!("{} %", network.strength))
+            .width_chars(5)
+            .xalign(1.)
+            .build();
+        strength.add_css_class("dim-label");
+        row.append(&strength);
+
+        if network.active {
+            row.append(
+                &gtk::Label::builder()
+                    .label("Connected")
+                    .width_chars(10)
+                    .build(),
+            );
+            return row;
+        }
+        let button = gtk::Button::builder()
+            .label("Connect")
+            .valign(gtk::Align::Center)
+            .width_request(90)
+            .build();
+        let interface = interface.to_owned();
+        let ssid = network.ssid.clone();
+        let secured = network.secured;
+        button.connect_clicked(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_| {
+                if secured {
+                    page.ask_password(interface.clone(), ssid.clone());
+                } else {
+                    page.emit_by_name::<()>("connect-wifi", &[&interface, &ssid, &""]);
+                }
+            }
+        ));
+        row.append(&button);
+        row
+    }
+
+    fn ask_password(&self, interface: String, ssid: String) {
+        let entry = gtk::PasswordEntry::builder()
+            .show_peek_icon(true)
+            .activates_default(true)
+            .build();
+        let dialog = adw::AlertDialog::new(
+            Some("Wi-Fi password"),
+            Some(&format!(
+                "Enter the password of \"{ssid}\", or leave it empty if the network was joined before"
+            )),
+        );
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("connect", "Connect");
+        dialog.set_response_appearance("connect", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("connect"));
+        dialog.set_close_response("cancel");
+        dialog.set_extra_child(Some(&entry));
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            async move {
+                if dialog.choose_future(&page).await != "connect" {
+                    return;
+                }
+                let password = entry.text().to_string();
+                page.emit_by_name::<()>("connect-wifi", &[&interface, &ssid, &password]);
+            }
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+use givc_common::query::{TrustLevel, VMStatus};
+use gtk::prelude::*;
+use gtk::{gio, glib};
+
+use crate::config::NotificationsConfig;
+use crate::service_gobject::ServiceGObject;
+
+/// Unit state changes which the panel did not ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "LifecycleEvent")]
+pub enum LifecycleEvent {
+    VmDown,
+    VmPaused,
+    AppStopped,
+    TrustDropped,
+}
+
+impl LifecycleEvent {
+    /// Event for `obj` after a status update from `status` and `trust_level`.
+    /// Status changes following a request of the panel are expected and not reported.
+    pub fn detect(
+        obj: &ServiceGObject,
+        status: VMStatus,
+        trust_level: TrustLevel,
+        requested: bool,
+    ) -> Option<Self> {
+        if !matches!(trust_level, TrustLevel::NotSecure)
+            && matches!(obj.trust_level(), TrustLevel::NotSecure)
+        {
+            return Some(Self::TrustDropped);
+        }
+        if requested {
+            return None;
+        }
+        match (status, obj.status()) {
+            (VMStatus::Running | VMStatus::Paused, VMStatus::PoweredOff) if obj.is_vm() => {
+                Some(Self::VmDown)
+            }
+            (VMStatus::Running, VMStatus::Paused) if obj.is_vm() => Some(Self::VmPaused),
+            (VMStatus::Running | VMStatus::Paused, VMStatus::PoweredOff) if obj.is_app() => {
+                Some(Self::AppStopped)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_enabled(self, config: &NotificationsConfig) -> bool {
+        match self {
+            Self::VmDown => config.vm_down,
+            Self::VmPaused => config.vm_paused,
+            Self::AppStopped => config.app_stopped,
+            Self::TrustDropped => config.trust_dropped,
+        }
+    }
+
+    /// Notification with "Restart" and "Open panel" buttons, wired to the `app.restart-unit`
+    /// and `app.show-unit` actions
+    pub fn notification(self, obj: &ServiceGObject) -> gio::Notification {
+        let name = if obj.display_name().is_empty() {
+            obj.name()
+        } else {
+            obj.display_name()
+        };
+        let (title, body) = match self {
+            Self::VmDown => ("VM stopped", format!("{name} went down unexpectedly")),
+            Self::VmPaused => ("VM paused", format!("{name} was paused")),
+            Self::AppStopped => (
+                "App stopped",
+                format!("{name} in {vm} stopped", vm = obj.vm_name()),
+            ),
+            Self::TrustDropped => ("Security warning", format!("{name} is not secure")),
+        };
+        let unit = obj.name().to_variant();
+
+        let notification = gio::Notification::new(title);
+        notification.set_body(Some(&body));
+        if self == Self::TrustDropped {
+            notification.set_priority(gio::NotificationPriority::High);
+        }
+        // Stopped apps are gone from the unit list, there is nothing to restart
+        if matches!(self, Self::VmDown | Self::VmPaused) {
+            notification.add_button_with_target_value("Restart", "app.restart-unit", Some(&unit));
+        }
+        notification.add_button_with_target_value("Open panel", "app.show-unit", Some(&unit));
+        notification.set_default_action_and_target_value("app.show-unit", Some(&unit));
+        notification
+    }
+}
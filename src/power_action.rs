@@ -0,0 +1,33 @@
+use gtk::glib;
+
+/// Actions on the whole device, see `ControlPanelGuiApplication::request_power_action`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "CtrlPowerAction")]
+#[repr(u8)]
+pub enum PowerAction {
+    Suspend,
+    Reboot,
+    PowerOff,
+    LogOut,
+}
+
+impl PowerAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerAction::Suspend => "Suspend",
+            PowerAction::Reboot => "Reboot",
+            PowerAction::PowerOff => "Power off",
+            PowerAction::LogOut => "Log out",
+        }
+    }
+
+    /// Completes "Failed to …" and "Do you want to …?"
+    pub fn verb(self) -> &'static str {
+        match self {
+            PowerAction::Suspend => "suspend the device",
+            PowerAction::Reboot => "reboot the device",
+            PowerAction::PowerOff => "power off the device",
+            PowerAction::LogOut => "log out",
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use gtk::glib;
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{CompositeTemplate, glib};
+    use std::sync::OnceLock;
+
+    use crate::power_action::PowerAction;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/power_settings_page.ui")]
+    pub struct PowerSettingsPage {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PowerSettingsPage {
+        const NAME: &'static str = "PowerSettingsPage";
+        type Type = super::PowerSettingsPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl PowerSettingsPage {
+        #[template_callback]
+        fn on_suspend_clicked(&self) {
+            self.emit_power_action(PowerAction::Suspend);
+        }
+
+        #[template_callback]
+        fn on_reboot_clicked(&self) {
+            self.emit_power_action(PowerAction::Reboot);
+        }
+
+        #[template_callback]
+        fn on_power_off_clicked(&self) {
+            self.emit_power_action(PowerAction::PowerOff);
+        }
+
+        #[template_callback]
+        fn on_log_out_clicked(&self) {
+            self.emit_power_action(PowerAction::LogOut);
+        }
+    }
+
+    impl PowerSettingsPage {
+        /// Only a request, the application asks for confirmation first
+        fn emit_power_action(&self, action: PowerAction) {
+            self.obj().emit_by_name::<()>("power-action", &[&action]);
+        }
+    }
+
+    impl ObjectImpl for PowerSettingsPage {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("power-action")
+                        .param_types([PowerAction::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for PowerSettingsPage {}
+    impl BoxImpl for PowerSettingsPage {}
+}
+
+glib::wrapper! {
+pub struct PowerSettingsPage(ObjectSubclass<imp::PowerSettingsPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for PowerSettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PowerSettingsPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+}
@@ -2,12 +2,38 @@ use gtk::glib;
 
 use givc_common::query::TrustLevel;
 
+/// Source shown for trust levels without a reason of their own
+const ADMIN_SERVICE_SOURCE: &str = "Reported by the Ghaf admin service";
+
+/// Title, meaning and recommended remediation of a trust level
+pub fn describe(trust_level: TrustLevel) -> (&'static str, &'static str, &'static str) {
+    match trust_level {
+        TrustLevel::Secure => (
+            "Secure",
+            "All security checks passed and the unit runs as expected.",
+            "No action needed.",
+        ),
+        TrustLevel::Warning => (
+            "Security warning",
+            "The security state could not be fully verified, some checks are missing or \
+             still running.",
+            "Use it with care. Restart the unit and contact your administrator if the \
+             warning persists.",
+        ),
+        TrustLevel::NotSecure => (
+            "Security alert",
+            "A security check failed, the unit may be misconfigured or compromised.",
+            "Do not use it for sensitive data. Shut it down and contact your administrator.",
+        ),
+    }
+}
+
 mod imp {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
 
     use givc_common::query::TrustLevel;
     use glib::Properties;
-    use gtk::{Image, Label, Orientation, glib, prelude::*, subclass::prelude::*};
+    use gtk::{Image, Label, Orientation, Popover, glib, prelude::*, subclass::prelude::*};
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::SecurityIcon)]
@@ -15,11 +41,16 @@ mod imp {
         #[property(get, set = SecurityIcon::set_trust_level, construct, builder(TrustLevel::Warning))]
         trust_level: Cell<TrustLevel>,
 
+        /// Why the unit has this trust level, shown as its source in the details
+        #[property(get, set)]
+        trust_reason: RefCell<String>,
+
         #[property(get, set, construct_only)]
         show_label: Cell<bool>,
 
         image: Image,
         label: Label,
+        popover: Popover,
     }
 
     #[glib::object_subclass]
@@ -42,12 +73,36 @@ mod imp {
             self.label.set_margin_start(5);
             self.obj().append(&self.image);
             self.obj().append(&self.label);
+
+            // Details are shown on click, without taking focus from the list
+            self.popover.set_parent(&*self.obj());
+            self.popover.set_autohide(true);
+            let click = gtk::GestureClick::new();
+            click.connect_released(glib::clone!(
+                #[weak(rename_to = icon)]
+                self.obj(),
+                move |gesture, _, _, _| {
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                    icon.imp().show_details();
+                }
+            ));
+            self.obj().add_controller(click);
+            self.obj().set_cursor_from_name(Some("pointer"));
             self.parent_constructed();
         }
+
+        fn dispose(&self) {
+            self.popover.unparent();
+        }
     }
 
     impl BoxImpl for SecurityIcon {}
-    impl WidgetImpl for SecurityIcon {}
+    impl WidgetImpl for SecurityIcon {
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            self.popover.present();
+        }
+    }
 
     impl SecurityIcon {
         fn set_trust_level(&self, trust_level: TrustLevel) {
@@ -63,6 +118,51 @@ mod imp {
                 TrustLevel::NotSecure => "Security alert!",
             });
         }
+
+        fn show_details(&self) {
+            let (title, meaning, remediation) = super::describe(self.trust_level.get());
+            let reason = self.trust_reason.borrow();
+            let source = if reason.is_empty() {
+                super::ADMIN_SERVICE_SOURCE
+            } else {
+                reason.as_str()
+            };
+
+            let content = gtk::Box::builder()
+                .orientation(Orientation::Vertical)
+                .spacing(8)
+                .margin_start(5)
+                .margin_end(5)
+                .margin_top(5)
+                .margin_bottom(5)
+                .build();
+            let heading = Label::builder()
+                .label(title)
+                .xalign(0.)
+                .css_classes(["heading"])
+                .build();
+            content.append(&heading);
+            for (caption, text) in [
+                (None, meaning),
+                (Some("Source"), source),
+                (Some("Recommended"), remediation),
+            ] {
+                let text = match caption {
+                    Some(caption) => format!("{caption}: {text}"),
+                    None => text.to_owned(),
+                };
+                content.append(
+                    &Label::builder()
+                        .label(text)
+                        .xalign(0.)
+                        .wrap(true)
+                        .max_width_chars(40)
+                        .build(),
+                );
+            }
+            self.popover.set_child(Some(&content));
+            self.popover.popup();
+        }
     }
 }
 
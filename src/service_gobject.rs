@@ -5,7 +5,6 @@ use givc_common::query::{QueryResult, TrustLevel, VMStatus};
 use givc_common::types::{ServiceType, VmType};
 
 use crate::prelude::*;
-use crate::wireguard_vms::static_contains;
 
 mod imp {
     use gtk::glib::{self, Properties};
@@ -26,7 +25,8 @@ mod imp {
         pub details: String,
         pub status: VMStatus,
         pub trust_level: TrustLevel,
-        pub has_wireguard: bool,
+        /// Why the unit has its trust level, empty when reported by the admin service
+        pub trust_reason: String,
     }
 
     impl Default for ServiceData {
@@ -41,7 +41,7 @@ mod imp {
                 details: String::new(),
                 status: VMStatus::default(),
                 trust_level: TrustLevel::default(),
-                has_wireguard: false,
+                trust_reason: String::new(),
             }
         }
     }
@@ -58,7 +58,7 @@ mod imp {
         #[property(name = "details", get, set, type = String, member = details)]
         #[property(name = "status", get, set, type = VMStatus, member = status, builder(VMStatus::default()))]
         #[property(name = "trust-level", get, set, type = TrustLevel, member = trust_level, builder(TrustLevel::default()))]
-        #[property(name = "has-wireguard", get, set, type = bool, member = has_wireguard)]
+        #[property(name = "trust-reason", get, set, type = String, member = trust_reason)]
         pub data: RefCell<ServiceData>,
     }
 
@@ -115,8 +115,6 @@ impl ServiceGObject {
                 details: {details}, status: {status}",
         );
 
-        let has_wireguard = is_vm && vm_name.is_some_and(static_contains);
-
         Object::builder()
             .property("name", name)
             .property("display-name", display_name)
@@ -128,13 +126,13 @@ impl ServiceGObject {
             //for demo
             .property("status", status)
             .property("trust-level", trust_level.into()) //trust_level as u8)
-            .property("has-wireguard", has_wireguard)
             .build()
     }
 
     pub fn update(&self, query_result: QueryResult) {
         self.set_property("details", query_result.description);
         self.set_property("status", query_result.status);
+        self.set_property("trust-level", query_result.trust_level);
     }
 
     pub fn is_vm_running(&self) -> bool {
@@ -145,6 +143,20 @@ impl ServiceGObject {
         !self.is_vm() && !self.is_app()
     }
 
+    /// Status as reported to scripts and other components
+    pub fn status_name(&self) -> &'static str {
+        match self.status() {
+            VMStatus::Running => "running",
+            VMStatus::PoweredOff => "powered-off",
+            VMStatus::Paused => "paused",
+        }
+    }
+
+    /// Admin, system VMs and the host itself cannot be controlled from the panel
+    pub fn is_controllable(&self) -> bool {
+        !matches!(self.vm_type(), VmType::AdmVM | VmType::SysVM | VmType::Host)
+    }
+
     pub fn sort_key(&self) -> (bool, String, bool, String) {
         let vm_name = self.vm_name();
         (
@@ -2,58 +2,96 @@ pub use givc_client::client::StatsResponse;
 pub use givc_common::pb::admin::StartResponse;
 
 use givc_client::endpoint::TlsConfig;
+use givc_common::query::TrustLevel;
+use givc_common::types::VmType;
 use glib::subclass::prelude::*;
 use gtk::{self, gio, glib};
+use serde::Serialize;
 
+use crate::control_action::ControlAction;
+use crate::endpoint::Endpoint;
+use crate::notifications::LifecycleEvent;
 use crate::prelude::*;
 use crate::service_gobject::ServiceGObject;
 
-#[derive(Debug, Clone)]
+/// Maximum number of control requests a bulk action keeps in flight
+const MAX_CONCURRENT_ACTIONS: usize = 4;
+/// Maximum number of requests in flight on the admin connection, more than a bulk action
+/// takes so stats and settings requests are not held up by one
+const MAX_CONCURRENT_REQUESTS: usize = 2 * MAX_CONCURRENT_ACTIONS;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct HostSysinfoStatus {
     pub ghaf_version: String,
     pub secure_boot: Option<bool>,
     pub disk_encryption: Option<bool>,
 }
 
+impl HostSysinfoStatus {
+    /// Host trust level with its reason, secure only when both protections are known enabled
+    pub fn trust(&self) -> (TrustLevel, String) {
+        let state = |enabled: Option<bool>| match enabled {
+            Some(true) => "enabled",
+            Some(false) => "disabled",
+            None => "unknown",
+        };
+        let reason = format!(
+            "Secure boot {secure_boot}, disk encryption {disk_encryption}",
+            secure_boot = state(self.secure_boot),
+            disk_encryption = state(self.disk_encryption)
+        );
+        let level = match (self.secure_boot, self.disk_encryption) {
+            (Some(true), Some(true)) => TrustLevel::Secure,
+            (Some(false), _) | (_, Some(false)) => TrustLevel::NotSecure,
+            _ => TrustLevel::Warning,
+        };
+        (level, reason)
+    }
+}
+
 mod imp {
     #![cfg_attr(feature = "mock", allow(unused_imports, dead_code))]
 
-    use std::cell::{Cell, RefCell};
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::sync::OnceLock;
     use std::thread;
     use std::time::Duration;
 
     use anyhow::Context;
     use async_channel::Sender;
+    use futures::StreamExt;
     use gio::{ListModel, subclass::prelude::*};
     use givc_client::endpoint::TlsConfig;
     use givc_client::{self, AdminClient};
-    use givc_common::{address::EndpointAddress, query::Event};
+    use givc_common::query::{Event, TrustLevel, VMStatus};
     use glib::JoinHandle;
-    use glib::{Object, Properties, SourceId};
+    use glib::subclass::Signal;
+    use glib::{Object, SourceId};
     use gtk::{gio, glib, prelude::*};
     use tokio::runtime::Builder;
 
-    use super::{HostSysinfoStatus, StartResponse, StatsResponse};
+    use super::{HostSysinfoStatus, MAX_CONCURRENT_REQUESTS, StartResponse, StatsResponse};
 
+    use crate::control_action::ControlAction;
+    use crate::endpoint::Endpoint;
+    use crate::notifications::LifecycleEvent;
     use crate::prelude::*;
     use crate::service_gobject::ServiceGObject;
 
     type TaskSender = Sender<(Task, Sender<Response>)>;
 
-    #[derive(Default, Properties)]
-    #[properties(wrapper_type = super::ServiceModel)]
+    #[derive(Default)]
     pub struct ServiceModel {
         services: RefCell<Vec<ServiceGObject>>,
 
-        #[property(set = ServiceModel::set_address, get)]
-        address: RefCell<String>,
-
-        #[property(set = ServiceModel::set_port, get = ServiceModel::get_port, type = u32)]
-        port: Cell<u16>,
-
+        /// Admin service endpoints, tried in order until one answers
+        endpoints: RefCell<Vec<Endpoint>>,
         reconnect_timeout: RefCell<Option<SourceId>>,
         tls_info: RefCell<Option<(String, TlsConfig)>>,
         task_runner: RefCell<Option<TaskSender>>,
+        /// Units asked to stop, pause or restart, whose next status change is expected
+        pub(super) requested: RefCell<HashSet<String>>,
         #[cfg(not(feature = "mock"))]
         join_handle: RefCell<Option<JoinHandle<()>>>,
     }
@@ -85,8 +123,38 @@ mod imp {
     }
 
     // Trait shared by all GObjects
-    #[glib::derived_properties]
-    impl ObjectImpl for ServiceModel {}
+    impl ObjectImpl for ServiceModel {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    // The initial unit list has been received
+                    Signal::builder("connected").build(),
+                    // None of the endpoints answered
+                    Signal::builder("connection-failed").build(),
+                    Signal::builder("unit-status-changed")
+                        .param_types([ServiceGObject::static_type()])
+                        .build(),
+                    // Any event received from the admin service, by name
+                    Signal::builder("watch-event")
+                        .param_types([ServiceGObject::static_type(), String::static_type()])
+                        .build(),
+                    // A control request finished, with the error message if it failed
+                    Signal::builder("control-action-done")
+                        .param_types([
+                            ServiceGObject::static_type(),
+                            ControlAction::static_type(),
+                            Option::<String>::static_type(),
+                        ])
+                        .build(),
+                    // A status change nobody asked the panel for
+                    Signal::builder("lifecycle-event")
+                        .param_types([ServiceGObject::static_type(), LifecycleEvent::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
 
     #[derive(Debug)]
     pub enum Response {
@@ -266,20 +334,11 @@ mod imp {
             res_rx.recv().await?.try_into()
         }
 
-        fn set_address(&self, address: String) {
-            *self.address.borrow_mut() = address;
-            self.delayed_reconnect();
-        }
-
-        fn set_port(&self, port: u32) {
-            self.port.set(port.try_into().unwrap_or(0));
+        pub(super) fn set_endpoints(&self, endpoints: Vec<Endpoint>) {
+            *self.endpoints.borrow_mut() = endpoints;
             self.delayed_reconnect();
         }
 
-        fn get_port(&self) -> u32 {
-            u32::from(self.port.get())
-        }
-
         #[allow(clippy::cast_possible_truncation)]
         fn extend<T>(&self, iter: impl IntoIterator<Item = T>)
         where
@@ -290,19 +349,20 @@ mod imp {
 
             let n = self.services.borrow().len();
             if n == 0 {
-                self.services.borrow_mut().extend(
-                    iter.into_iter()
-                        .map(ServiceGObject::from)
-                        .chain(Some(ServiceGObject::new(
-                            "ghaf-host",
-                            "Host operating system",
-                            VMStatus::Running,
-                            TrustLevel::Warning,
-                            ServiceType::VM,
-                            Some("ghaf-host"),
-                            VmType::Host,
-                        ))),
+                let host = ServiceGObject::new(
+                    "ghaf-host",
+                    "Host operating system",
+                    VMStatus::Running,
+                    TrustLevel::Warning,
+                    ServiceType::VM,
+                    Some("ghaf-host"),
+                    VmType::Host,
                 );
+                // Replaced by `update_host_trust` once the host sysinfo is known
+                host.set_trust_reason("Host security status not retrieved yet");
+                self.services
+                    .borrow_mut()
+                    .extend(iter.into_iter().map(ServiceGObject::from).chain(Some(host)));
                 self.services
                     .borrow_mut()
                     .sort_by_cached_key(ServiceGObject::sort_key);
@@ -377,6 +437,20 @@ mod imp {
             ]);
         }
 
+        fn emit_lifecycle_event(
+            &self,
+            obj: &ServiceGObject,
+            status: VMStatus,
+            trust_level: TrustLevel,
+        ) {
+            let requested = self.requested.borrow_mut().remove(&obj.name());
+            if let Some(event) = LifecycleEvent::detect(obj, status, trust_level, requested) {
+                debug!("Lifecycle event {event:?} of {name}", name = obj.name());
+                self.obj()
+                    .emit_by_name::<()>("lifecycle-event", &[obj, &event]);
+            }
+        }
+
         pub(super) fn find(
             &self,
             pred: impl Fn(&ServiceGObject) -> bool,
@@ -388,8 +462,8 @@ mod imp {
                 .find_map(|(pos, obj)| pred(obj).then(|| (pos, obj.clone())))
         }
 
-        pub(super) fn set_tls_info(&self, name: String, config: TlsConfig) {
-            *self.tls_info.borrow_mut() = Some((name, config));
+        pub(super) fn set_tls_info(&self, tls_info: Option<(String, TlsConfig)>) {
+            *self.tls_info.borrow_mut() = tls_info;
             self.delayed_reconnect();
         }
 
@@ -399,6 +473,8 @@ mod imp {
             use givc_common::query::{TrustLevel, VMStatus};
             use givc_common::types::{ServiceType, VmType};
             self.fill_by_mock_data();
+            self.obj().emit_by_name::<()>("connected", &[]);
+            self.obj().update_host_trust().await;
 
             glib::spawn_future_local(glib::clone!(
                 #[strong(rename_to = model)]
@@ -446,13 +522,10 @@ mod imp {
             if let Some(join) = join {
                 let _ = join.await;
             }
-            if self.address.borrow().is_empty() || self.port.get() == 0 {
+            let endpoints = self.endpoints.borrow().clone();
+            if endpoints.is_empty() {
                 return;
             }
-            let address = EndpointAddress::Tcp {
-                addr: self.address.borrow().clone(),
-                port: self.port.get(),
-            };
             let tls_info = self.tls_info.borrow().as_ref().cloned();
 
             let (event_tx, event_rx) = async_channel::unbounded();
@@ -467,18 +540,22 @@ mod imp {
                     .unwrap()
                     .block_on(async move {
                         let timeout_duration = Duration::from_secs(5);
-                        let admin_client = AdminClient::from_endpoint_address(address, tls_info);
-                        let result = {
-                            tokio::select! {
+                        let mut connection = None;
+                        for endpoint in endpoints {
+                            let admin_client = AdminClient::from_endpoint_address(
+                                endpoint.to_address(),
+                                tls_info.clone(),
+                            );
+                            let result = tokio::select! {
                                 () = tokio::time::sleep(timeout_duration) => {
-                                    warn!("Watch call timeout");
-                                    return;
+                                    warn!("Watch call to {endpoint} timeout");
+                                    None
                                 },
                                 result = admin_client.watch() => match result {
-                                    Ok(result) => result,
+                                    Ok(result) => Some(result),
                                     Err(e) => {
-                                        error!("Watch call failed: {e}");
-                                        return;
+                                        error!("Watch call to {endpoint} failed: {e}");
+                                        None
                                     }
                                 },
                                 () = async {
@@ -486,15 +563,28 @@ mod imp {
                                         debug!("Not yet connected, task ignored");
                                     }
                                 } => return,
+                            };
+                            if let Some(result) = result {
+                                info!("Connected to admin service at {endpoint}");
+                                connection = Some((admin_client, result));
+                                break;
                             }
+                        }
+                        let Some((admin_client, result)) = connection else {
+                            return;
                         };
-                        debug!("Connected!");
 
                         let _ = event_tx.send((result.channel, result.initial)).await;
-                        while let Ok((task, resp)) = task_rx.recv().await {
-                            let res = task(&admin_client).await;
-                            let _ = resp.send(res).await;
-                        }
+                        // Run tasks concurrently, up to a limit
+                        task_rx
+                            .for_each_concurrent(Some(MAX_CONCURRENT_REQUESTS), |(task, resp)| {
+                                let admin_client = &admin_client;
+                                async move {
+                                    let res = task(admin_client).await;
+                                    let _ = resp.send(res).await;
+                                }
+                            })
+                            .await;
                     });
             });
 
@@ -510,6 +600,12 @@ mod imp {
                             model.items_changed(0, n as u32, 0);
                         }
                         this.extend(initial);
+                        model.emit_by_name::<()>("connected", &[]);
+                        glib::spawn_future_local(glib::clone!(
+                            #[strong]
+                            model,
+                            async move { model.update_host_trust().await }
+                        ));
 
                         while let Ok(event) = channel.recv().await {
                             match event {
@@ -518,7 +614,15 @@ mod imp {
                                     if let Some((_, obj)) =
                                         this.find(|obj| obj.name() == result.name)
                                     {
+                                        let (status, trust_level) =
+                                            (obj.status(), obj.trust_level());
                                         obj.update(result);
+                                        model.emit_by_name::<()>("unit-status-changed", &[&obj]);
+                                        model.emit_by_name::<()>(
+                                            "watch-event",
+                                            &[&obj, &"status-changed"],
+                                        );
+                                        this.emit_lifecycle_event(&obj, status, trust_level);
                                     }
                                 }
                                 Event::UnitShutdown(result) => {
@@ -528,20 +632,40 @@ mod imp {
                                     if let Some((pos, obj)) =
                                         this.find(|obj| obj.name() == result.name)
                                     {
+                                        let (status, trust_level) =
+                                            (obj.status(), obj.trust_level());
                                         if obj.is_vm() {
                                             obj.update(result);
+                                            model
+                                                .emit_by_name::<()>("unit-status-changed", &[&obj]);
                                         } else {
                                             this.services.borrow_mut().remove(pos);
                                             model.items_changed(pos as u32, 1, 0);
+                                            obj.set_status(VMStatus::PoweredOff);
                                         }
+                                        model.emit_by_name::<()>(
+                                            "watch-event",
+                                            &[&obj, &"shutdown"],
+                                        );
+                                        this.emit_lifecycle_event(&obj, status, trust_level);
                                     }
                                 }
                                 Event::UnitRegistered(result) => {
                                     debug!("Unit registered {result:?}");
+                                    let name = result.name.clone();
                                     this.extend(Some(result));
+                                    if let Some((_, obj)) = this.find(|obj| obj.name() == name) {
+                                        model.emit_by_name::<()>(
+                                            "watch-event",
+                                            &[&obj, &"registered"],
+                                        );
+                                    }
                                 }
                             }
                         }
+                    } else if model.imp().task_runner.borrow().is_some() {
+                        // Not replaced by a newer connection attempt
+                        model.emit_by_name::<()>("connection-failed", &[]);
                     }
                 }
             )));
@@ -561,8 +685,112 @@ impl Default for ServiceModel {
 }
 
 impl ServiceModel {
-    pub fn set_tls_info(&self, name: String, config: TlsConfig) {
-        self.imp().set_tls_info(name, config);
+    /// Replaces the TLS server name and certificates, `None` connects without TLS
+    pub fn set_tls_info(&self, tls_info: Option<(String, TlsConfig)>) {
+        self.imp().set_tls_info(tls_info);
+    }
+
+    /// Replaces the admin service endpoints, the first one is tried first
+    pub fn set_endpoints(&self, endpoints: Vec<Endpoint>) {
+        self.imp().set_endpoints(endpoints);
+    }
+
+    /// Derives the host trust level from its secure boot and disk encryption status
+    async fn update_host_trust(&self) {
+        let Some((_, host)) = self.imp().find(|obj| matches!(obj.vm_type(), VmType::Host)) else {
+            return;
+        };
+        match self.get_sysinfo_status_from_host().await {
+            Ok(sysinfo) => {
+                let (trust_level, reason) = sysinfo.trust();
+                host.set_trust_level(trust_level);
+                host.set_trust_reason(reason);
+                self.emit_by_name::<()>("unit-status-changed", &[&host]);
+            }
+            Err(e) => warn!("Cannot determine the host trust level: {e}"),
+        }
+    }
+
+    pub fn connect_unit_status_changed<F: Fn(&Self, &ServiceGObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("unit-status-changed", false, move |values| {
+            let model = values[0].get::<Self>().unwrap();
+            let obj = values[1].get::<ServiceGObject>().unwrap();
+            f(&model, &obj);
+            None
+        })
+    }
+
+    pub fn connect_lifecycle_event<F: Fn(&Self, &ServiceGObject, LifecycleEvent) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("lifecycle-event", false, move |values| {
+            let model = values[0].get::<Self>().unwrap();
+            let obj = values[1].get::<ServiceGObject>().unwrap();
+            let event = values[2].get::<LifecycleEvent>().unwrap();
+            f(&model, &obj, event);
+            None
+        })
+    }
+
+    pub fn connect_watch_event<F: Fn(&Self, &ServiceGObject, &str) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("watch-event", false, move |values| {
+            let model = values[0].get::<Self>().unwrap();
+            let obj = values[1].get::<ServiceGObject>().unwrap();
+            let event = values[2].get::<String>().unwrap();
+            f(&model, &obj, &event);
+            None
+        })
+    }
+
+    pub fn connect_control_action_done<
+        F: Fn(&Self, &ServiceGObject, ControlAction, Option<String>) + 'static,
+    >(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("control-action-done", false, move |values| {
+            let model = values[0].get::<Self>().unwrap();
+            let obj = values[1].get::<ServiceGObject>().unwrap();
+            let action = values[2].get::<ControlAction>().unwrap();
+            let error = values[3].get::<Option<String>>().unwrap();
+            f(&model, &obj, action, error);
+            None
+        })
+    }
+
+    /// Waits until the admin service has sent the initial unit list
+    pub async fn wait_connected(&self) -> Result<(), anyhow::Error> {
+        let (tx, rx) = async_channel::bounded(1);
+        let connected = self.connect_local(
+            "connected",
+            false,
+            glib::clone!(
+                #[strong]
+                tx,
+                move |_| {
+                    let _ = tx.try_send(true);
+                    None
+                }
+            ),
+        );
+        let failed = self.connect_local("connection-failed", false, move |_| {
+            let _ = tx.try_send(false);
+            None
+        });
+        let result = rx.recv().await;
+        self.disconnect(connected);
+        self.disconnect(failed);
+        match result {
+            Ok(true) => Ok(()),
+            _ => anyhow::bail!("Cannot connect to the admin service"),
+        }
     }
 
     pub async fn start_service(&self, obj: ServiceGObject) -> Result<StartResponse, anyhow::Error> {
@@ -628,6 +856,90 @@ impl ServiceModel {
             .await
     }
 
+    pub async fn control_service(
+        &self,
+        action: ControlAction,
+        obj: &ServiceGObject,
+    ) -> Result<(), anyhow::Error> {
+        let requested = &self.imp().requested;
+        if matches!(
+            action,
+            ControlAction::Restart | ControlAction::Pause | ControlAction::Shutdown
+        ) {
+            requested.borrow_mut().insert(obj.name());
+        }
+        let result = match action {
+            ControlAction::Start => self.start_service(obj.clone()).await.map(|_| ()),
+            ControlAction::Restart => self.restart_service(obj).await.map(|_| ()),
+            ControlAction::Pause => self.pause_service(obj).await,
+            ControlAction::Resume => self.resume_service(obj).await,
+            ControlAction::Shutdown => self.stop_service(obj).await,
+        };
+        if result.is_err() {
+            requested.borrow_mut().remove(&obj.name());
+        }
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.emit_by_name::<()>("control-action-done", &[obj, &action, &error]);
+        result
+    }
+
+    /// Applies `action` to every object, keeping at most `MAX_CONCURRENT_ACTIONS` requests in
+    /// flight. Results are returned in the order of `objects`.
+    pub async fn control_services(
+        &self,
+        action: ControlAction,
+        objects: Vec<ServiceGObject>,
+    ) -> Vec<(ServiceGObject, Result<(), anyhow::Error>)> {
+        use futures::stream::{self, StreamExt};
+
+        stream::iter(objects)
+            .map(|obj| async move {
+                let result = self.control_service(action, &obj).await;
+                (obj, result)
+            })
+            .buffered(MAX_CONCURRENT_ACTIONS)
+            .collect()
+            .await
+    }
+
+    /// App VMs currently running, the ones a power action would cut short
+    pub fn running_app_vms(&self) -> Vec<ServiceGObject> {
+        self.clone()
+            .wrap::<ServiceGObject>()
+            .iter()
+            .filter(|obj| obj.is_vm_running() && matches!(obj.vm_type(), VmType::AppVM))
+            .collect()
+    }
+
+    /// Shuts `vms` down one after the other, stopping at the first failure. Goes through
+    /// `control_service` so the shutdowns are not reported as unexpected.
+    pub async fn stop_vms_in_sequence(&self, vms: &[ServiceGObject]) -> Result<(), anyhow::Error> {
+        for vm in vms {
+            self.control_service(ControlAction::Shutdown, vm)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot stop {name}: {e}", name = vm.vm_name()))?;
+        }
+        Ok(())
+    }
+
+    pub async fn suspend_system(&self) -> Result<(), anyhow::Error> {
+        self.imp()
+            .client_cmd(async |client| client.suspend().await)
+            .await
+    }
+
+    pub async fn reboot_system(&self) -> Result<(), anyhow::Error> {
+        self.imp()
+            .client_cmd(async |client| client.reboot().await)
+            .await
+    }
+
+    pub async fn power_off_system(&self) -> Result<(), anyhow::Error> {
+        self.imp()
+            .client_cmd(async |client| client.poweroff().await)
+            .await
+    }
+
     pub async fn set_locale(&self, locale: String) -> Result<(), anyhow::Error> {
         self.imp()
             .client_cmd(async |client| client.set_locale(locale).await)
@@ -687,15 +999,4 @@ impl ServiceModel {
             disk_encryption: Some(false),
         })
     }
-
-    #[allow(clippy::unused_async, clippy::unused_self)]
-    pub async fn check_for_update(&self) -> Result<(), anyhow::Error> {
-        warn!("Check for update request");
-        Ok(())
-    }
-
-    #[allow(clippy::unused_self)]
-    pub fn update_request(&self) {
-        warn!("Update request");
-    }
 }
@@ -120,6 +120,13 @@ impl ServiceRow {
         // Save binding
         bindings.push(security_binding);
 
+        let trust_reason_binding = object
+            .bind_property("trust-reason", &security_icon, "trust-reason")
+            .sync_create()
+            .build();
+        // Save binding
+        bindings.push(trust_reason_binding);
+
         //block was left here as example
         /*/ Bind `task_object.completed` to `task_row.content_label.attributes`
         let content_label_binding = task_object
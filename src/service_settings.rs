@@ -1,9 +1,11 @@
-use givc_common::types::VmType;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
 
+use crate::prelude::*;
 use crate::service_gobject::ServiceGObject;
+use crate::settings_action::SettingsAction;
+use crate::vm_capabilities::Capability;
 use crate::window::ControlPanelGuiWindow;
 
 mod imp {
@@ -12,8 +14,8 @@ mod imp {
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
     use gtk::{
-        Button, CompositeTemplate, Label, MenuButton, Popover, Revealer, Separator, ToggleButton,
-        gio, glib,
+        Button, CompositeTemplate, Label, MenuButton, Popover, Revealer, Separator, Switch,
+        ToggleButton, gio, glib,
     };
     use std::cell::RefCell;
     use std::sync::OnceLock;
@@ -67,9 +69,13 @@ mod imp {
         #[template_child]
         pub security_icon: TemplateChild<SecurityIcon>,
         #[template_child]
-        pub wireguard_section_separator: TemplateChild<Separator>,
+        pub notifications_box: TemplateChild<gtk::Box>,
         #[template_child]
-        pub wireguard_button: TemplateChild<Button>,
+        pub notifications_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub capabilities_separator: TemplateChild<Separator>,
+        #[template_child]
+        pub capabilities_box: TemplateChild<gtk::Box>,
         #[template_child]
         pub control_label: TemplateChild<Label>,
         #[template_child]
@@ -78,6 +84,8 @@ mod imp {
         pub popover_menu: TemplateChild<Popover>,
         #[template_child]
         pub popover_menu_2: TemplateChild<Popover>,
+        #[template_child]
+        pub launch_with_button: TemplateChild<Button>,
 
         #[template_child]
         pub cpu_sys_serie: TemplateChild<Serie>,
@@ -112,17 +120,6 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl ServiceSettings {
-        #[template_callback]
-        fn on_wireguard_button_clicked(&self) {
-            if let Some(vm) = self.service.borrow().clone() {
-                debug!("Wireguard GUI will be launched...");
-                self.obj().emit_by_name::<()>(
-                    "settings-action",
-                    &[&SettingsAction::OpenWireGuard { vm }],
-                );
-            }
-        }
-
         #[template_callback]
         fn open_info(&self) {
             let value = self.arrow_button.is_active();
@@ -158,6 +155,27 @@ mod imp {
             self.emit_control_action(ControlAction::Pause);
             self.popover_menu_2.popdown();
         }
+
+        #[template_callback]
+        fn on_launch_with_clicked(&self) {
+            if let Some(app) = self.service.borrow().clone() {
+                self.obj()
+                    .emit_by_name::<()>("settings-action", &[&SettingsAction::LaunchWith { app }]);
+            }
+            self.popover_menu_2.popdown();
+        }
+
+        #[template_callback]
+        fn on_notifications_toggled(&self) {
+            let Some(vm) = self.service.borrow().clone().filter(ServiceGObject::is_vm) else {
+                return;
+            };
+            let action = SettingsAction::MuteNotifications {
+                vm: vm.vm_name(),
+                muted: !self.notifications_switch.is_active(),
+            };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
     } //end #[gtk::template_callbacks]
 
     impl ObjectImpl for ServiceSettings {
@@ -236,13 +254,8 @@ impl ServiceSettings {
         let control_label = self.imp().control_label.get();
         let mut bindings = self.imp().bindings.borrow_mut();
 
-        //wireguard label/button
-        self.imp()
-            .wireguard_section_separator
-            .set_visible(object.has_wireguard());
-        self.imp()
-            .wireguard_button
-            .set_visible(object.has_wireguard());
+        //capability buttons
+        self.show_capabilities(object);
         self.imp().resources_info_box.set_visible(object.is_vm());
 
         // kluge: Set menu button sensitive before changing its popover menu to avoid menu becoming
@@ -306,17 +319,25 @@ impl ServiceSettings {
                 c,
             ));
         } else {
+            self.imp().launch_with_button.set_visible(object.is_app());
             self.imp()
                 .action_menu_button
                 .set_popover(Some(&self.imp().popover_menu_2.get()));
         }
 
-        self.imp().action_menu_button.set_sensitive(!matches!(
-            object.vm_type(),
-            VmType::AdmVM | VmType::SysVM | VmType::Host
-        ));
+        self.imp()
+            .action_menu_button
+            .set_sensitive(object.is_controllable());
         *self.imp().service.borrow_mut() = Some(object.clone());
 
+        //per-VM notification mute
+        self.imp().notifications_box.set_visible(object.is_vm());
+        let muted = match self.root().and_downcast::<ControlPanelGuiWindow>() {
+            Some(win) if object.is_vm() => win.is_vm_muted(&object.vm_name()),
+            _ => false,
+        };
+        self.imp().notifications_switch.set_active(!muted);
+
         if is_vm_or_app {
             let full_service_name = self.imp().name_slot_2.get();
 
@@ -372,6 +393,12 @@ impl ServiceSettings {
             .build();
         bindings.push(security_icon_binding);
 
+        let trust_reason_binding = object
+            .bind_property("trust-reason", &security_icon, "trust-reason")
+            .sync_create()
+            .build();
+        bindings.push(trust_reason_binding);
+
         //change label
         let controls_title_binding = object
             .bind_property("is-vm", &control_label, "label")
@@ -387,6 +414,82 @@ impl ServiceSettings {
         bindings.push(controls_title_binding);
     }
 
+    /// Re-renders capability buttons of the bound VM, e.g. after the capabilities file changed
+    pub fn refresh_capabilities(&self) {
+        if let Some(object) = self.imp().service.borrow().clone() {
+            self.show_capabilities(&object);
+        }
+    }
+
+    fn show_capabilities(&self, object: &ServiceGObject) {
+        let capabilities_box = self.imp().capabilities_box.get();
+        while let Some(child) = capabilities_box.first_child() {
+            capabilities_box.remove(&child);
+        }
+
+        let capabilities = match self.root().and_downcast::<ControlPanelGuiWindow>() {
+            Some(win) if object.is_vm() => win.vm_capabilities(&object.vm_name()),
+            _ => Vec::new(),
+        };
+        self.imp()
+            .capabilities_separator
+            .set_visible(!capabilities.is_empty());
+        capabilities_box.set_visible(!capabilities.is_empty());
+
+        for capability in capabilities {
+            capabilities_box.append(&self.capability_button(object, capability));
+        }
+    }
+
+    fn capability_button(&self, vm: &ServiceGObject, capability: Capability) -> gtk::Button {
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .margin_start(5)
+            .margin_end(5)
+            .margin_top(5)
+            .margin_bottom(5)
+            .spacing(20)
+            .halign(gtk::Align::Start)
+            .build();
+        if let Some(icon) = &capability.icon {
+            content.append(&gtk::Image::from_icon_name(icon));
+        }
+        content.append(
+            &gtk::Label::builder()
+                .label(capability.label.as_str())
+                .halign(gtk::Align::Start)
+                .build(),
+        );
+        content.append(&gtk::Box::builder().hexpand(true).build());
+        content.append(
+            &gtk::Image::builder()
+                .icon_name("pan-end-symbolic")
+                .halign(gtk::Align::End)
+                .build(),
+        );
+
+        let button = gtk::Button::builder()
+            .child(&content)
+            .css_classes(["settings-button"])
+            .halign(gtk::Align::Start)
+            .build();
+        button.connect_clicked(glib::clone!(
+            #[weak(rename_to = settings)]
+            self,
+            #[strong]
+            vm,
+            move |_| {
+                debug!("{label} will be launched...", label = capability.label);
+                let action = SettingsAction::LaunchCapability {
+                    vm: vm.clone(),
+                    capability: capability.clone(),
+                };
+                settings.emit_by_name::<()>("settings-action", &[&action]);
+            }
+        ));
+        button
+    }
+
     pub fn unbind(&self) {
         // Unbind all stored bindings
         for binding in self.imp().bindings.borrow_mut().drain(..) {
@@ -0,0 +1,29 @@
+use zbus::{Connection, proxy};
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto",
+    gen_blocking = false
+)]
+trait Session {
+    fn terminate(&self) -> zbus::Result<()>;
+
+    fn set_brightness(&self, subsystem: &str, name: &str, brightness: u32) -> zbus::Result<()>;
+}
+
+/// Ends the session the panel runs in through logind, logging the user out
+pub async fn log_out() -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    SessionProxy::new(&connection).await?.terminate().await
+}
+
+/// Sets the raw level of a `backlight` or `leds` device, logind allows it without privileges
+/// for the devices of the active seat
+pub async fn set_brightness(subsystem: &str, name: &str, brightness: u32) -> zbus::Result<()> {
+    let connection = Connection::system().await?;
+    SessionProxy::new(&connection)
+        .await?
+        .set_brightness(subsystem, name, brightness)
+        .await
+}
@@ -1,8 +1,16 @@
-use gtk::gio::ListModel;
 use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
+use crate::audio_backend::AudioState;
+use crate::config::{ClockFormat, NightLightConfig};
+use crate::display_backend::Output;
+use crate::locale_provider::{
+    ClockStatus, KeyboardLayout, KeyboardLayouts, LanguageRegionEntry, RegionChange,
+};
+use crate::network_backend::NetworkState;
+use crate::updater::Updater;
+
 mod imp {
     use glib::Binding;
     use glib::subclass::Signal;
@@ -14,10 +22,20 @@ mod imp {
 
     //use crate::service_gobject::ServiceGObject; will be used in the future
     use crate::about::AboutPage;
+    use crate::audio_backend::DeviceKind;
+    use crate::audio_settings_page::AudioSettingsPage;
+    use crate::config::{ClockFormat, NightLightConfig};
     use crate::control_action::ControlAction;
+    use crate::date_time_settings_page::DateTimeSettingsPage;
+    use crate::display_backend::Mode;
+    use crate::display_settings_page::{self, DisplaySettingsPage};
     use crate::language_region_settings_page::LanguageRegionSettingsPage;
+    use crate::network_settings_page::NetworkSettingsPage;
+    use crate::power_action::PowerAction;
+    use crate::power_settings_page::PowerSettingsPage;
     use crate::service_gobject::ServiceGObject;
     use crate::settings_action::SettingsAction;
+    use crate::updates_page::UpdatesPage;
 
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/settings.ui")]
@@ -29,6 +47,18 @@ mod imp {
         #[template_child]
         pub language_region_settings_page: TemplateChild<LanguageRegionSettingsPage>,
         #[template_child]
+        pub date_time_settings_page: TemplateChild<DateTimeSettingsPage>,
+        #[template_child]
+        pub display_settings_page: TemplateChild<DisplaySettingsPage>,
+        #[template_child]
+        pub audio_settings_page: TemplateChild<AudioSettingsPage>,
+        #[template_child]
+        pub network_settings_page: TemplateChild<NetworkSettingsPage>,
+        #[template_child]
+        pub power_settings_page: TemplateChild<PowerSettingsPage>,
+        #[template_child]
+        pub updates_page: TemplateChild<UpdatesPage>,
+        #[template_child]
         pub about_page: TemplateChild<AboutPage>,
         //pub vm_model: RefCell<ListModel>,
 
@@ -65,29 +95,163 @@ mod imp {
             let action = SettingsAction::RegionNLanguage {
                 locale: "en_US.utf8".into(),
                 timezone: "UTC".into(),
+                keyboard: KeyboardLayout::default(),
             };
             self.obj().emit_by_name::<()>("settings-action", &[&action]);
             self.language_region_settings_page
                 .locale_select_find(|obj| obj.name() == "en_US.utf8");
             self.language_region_settings_page
                 .timezone_select_find(|obj| obj.name() == "UTC");
+            self.language_region_settings_page
+                .keyboard_select(&KeyboardLayout::default());
         }
 
         #[template_callback]
-        fn on_locale_timezone_changed(&self, locale: String, timezone: String) {
-            let action = SettingsAction::RegionNLanguage { locale, timezone };
+        fn on_locale_timezone_changed(
+            &self,
+            locale: String,
+            timezone: String,
+            layout: String,
+            variant: String,
+        ) {
+            let action = SettingsAction::RegionNLanguage {
+                locale,
+                timezone,
+                keyboard: KeyboardLayout { layout, variant },
+            };
             self.obj().emit_by_name::<()>("settings-action", &[&action]);
         }
 
         #[template_callback]
-        fn on_check_for_update_request(&self) {
-            let action = SettingsAction::CheckForUpdateRequest;
+        fn on_clock_format_changed(&self, hours12: bool) {
+            let format = if hours12 {
+                ClockFormat::Hours12
+            } else {
+                ClockFormat::Hours24
+            };
+            let action = SettingsAction::SetClockFormat { format };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_output_changed(
+            &self,
+            output: String,
+            width: u32,
+            height: u32,
+            refresh: f64,
+            scale: f64,
+        ) {
+            let mode = Mode {
+                width,
+                height,
+                refresh,
+            };
+            let action = SettingsAction::ConfigureOutput {
+                output,
+                mode,
+                scale,
+            };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_brightness_changed(&self, brightness: f64) {
+            let action = SettingsAction::SetBrightness { brightness };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_night_light_changed(&self, enabled: bool, start: u32, end: u32, temperature: u32) {
+            let (Some(start), Some(end)) = (
+                display_settings_page::time_of_day(start),
+                display_settings_page::time_of_day(end),
+            ) else {
+                return;
+            };
+            let night_light = NightLightConfig {
+                enabled,
+                start,
+                end,
+                temperature,
+            };
+            let action = SettingsAction::SetNightLight { night_light };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_audio_refresh_request(&self) {
+            let action = SettingsAction::AudioRefreshRequest;
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_default_audio_device_changed(&self, kind: DeviceKind, name: String) {
+            let action = SettingsAction::SetDefaultAudioDevice { kind, name };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_audio_device_volume_changed(&self, kind: DeviceKind, name: String, volume: f64) {
+            let action = SettingsAction::SetAudioDeviceVolume { kind, name, volume };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_audio_device_muted_changed(&self, kind: DeviceKind, name: String, muted: bool) {
+            let action = SettingsAction::SetAudioDeviceMuted { kind, name, muted };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_vm_volume_changed(&self, vm: String, volume: f64) {
+            let action = SettingsAction::SetVmVolume { vm, volume };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_vm_muted_changed(&self, vm: String, muted: bool) {
+            let action = SettingsAction::SetVmMuted { vm, muted };
             self.obj().emit_by_name::<()>("settings-action", &[&action]);
         }
 
         #[template_callback]
-        fn on_update_request(&self) {
-            let action = SettingsAction::UpdateRequest;
+        fn on_network_refresh_request(&self) {
+            let action = SettingsAction::NetworkRefreshRequest;
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_wifi_scan_request(&self, interface: String) {
+            let action = SettingsAction::ScanWifi { interface };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_connect_wifi(&self, interface: String, ssid: String, password: String) {
+            let action = SettingsAction::ConnectWifi {
+                interface,
+                ssid,
+                password,
+            };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_disconnect_interface(&self, interface: String) {
+            let action = SettingsAction::DisconnectInterface { interface };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_power_action(&self, action: PowerAction) {
+            let action = SettingsAction::Power { action };
+            self.obj().emit_by_name::<()>("settings-action", &[&action]);
+        }
+
+        #[template_callback]
+        fn on_check_for_update_request(&self) {
+            let action = SettingsAction::CheckForUpdateRequest;
             self.obj().emit_by_name::<()>("settings-action", &[&action]);
         }
     } //end #[gtk::template_callbacks]
@@ -136,16 +300,99 @@ impl Settings {
         glib::Object::builder().build()
     }
 
-    pub fn set_locale_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
+    pub fn set_locales(&self, locales: Vec<LanguageRegionEntry>, current: Option<&str>) {
+        self.imp()
+            .language_region_settings_page
+            .set_locales(locales, current);
+    }
+
+    pub fn set_timezones(&self, timezones: Vec<LanguageRegionEntry>, current: Option<&str>) {
         self.imp()
             .language_region_settings_page
-            .set_locale_model(model, selected);
+            .set_timezones(timezones, current);
     }
 
-    pub fn set_timezone_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
+    pub fn set_keyboard_layouts(
+        &self,
+        keyboard: KeyboardLayouts,
+        current: Option<&KeyboardLayout>,
+    ) {
         self.imp()
             .language_region_settings_page
-            .set_timezone_model(model, selected);
+            .set_keyboard_layouts(keyboard, current);
+    }
+
+    pub fn show_region_change(&self, change: &RegionChange) {
+        match change {
+            RegionChange::Clock(status) => self.set_clock_status(*status),
+            _ => self
+                .imp()
+                .language_region_settings_page
+                .show_region_change(change),
+        }
+    }
+
+    pub fn set_clock_status(&self, status: ClockStatus) {
+        self.imp().date_time_settings_page.set_status(status);
+    }
+
+    pub fn set_clock_format(&self, format: ClockFormat) {
+        self.imp().date_time_settings_page.set_clock_format(format);
+    }
+
+    pub fn set_outputs(&self, outputs: Vec<Output>) {
+        self.imp().display_settings_page.set_outputs(outputs);
+    }
+
+    pub fn set_brightness(&self, brightness: Option<f64>) {
+        self.imp().display_settings_page.set_brightness(brightness);
+    }
+
+    pub fn set_night_light(&self, config: &NightLightConfig) {
+        self.imp().display_settings_page.set_night_light(config);
+    }
+
+    /// Keeps the last state after a failed read
+    pub fn set_audio_state(&self, state: Result<AudioState, String>) {
+        let page = &self.imp().audio_settings_page;
+        match state {
+            Ok(state) => {
+                page.set_error(None);
+                page.set_state(state);
+            }
+            Err(e) => page.set_error(Some(&e)),
+        }
+    }
+
+    /// Keeps the last state after a failed read
+    pub fn set_network_state(&self, state: Result<NetworkState, String>) {
+        let page = &self.imp().network_settings_page;
+        match state {
+            Ok(state) => {
+                page.set_error(None);
+                page.set_state(state);
+            }
+            Err(e) => page.set_error(Some(&e)),
+        }
+    }
+
+    pub fn set_wifi_scanning(&self, scanning: bool) {
+        self.imp().network_settings_page.set_scanning(scanning);
+    }
+
+    pub fn set_updater(&self, updater: &Updater) {
+        self.imp().updates_page.set_updater(updater);
+    }
+
+    /// Selects the settings row named `name`, e.g. `locale` or `about`
+    pub fn show_page(&self, name: &str) {
+        let list_box = &self.imp().list_box;
+        if let Some(row) = (0..)
+            .map_while(|index| list_box.row_at_index(index))
+            .find(|row| row.widget_name() == name)
+        {
+            list_box.select_row(Some(&row));
+        }
     }
 
     pub fn init(&self) {
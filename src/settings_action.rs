@@ -1,4 +1,10 @@
+use crate::audio_backend::DeviceKind;
+use crate::config::{ClockFormat, NightLightConfig};
+use crate::display_backend::Mode;
+use crate::locale_provider::KeyboardLayout;
+use crate::power_action::PowerAction;
 use crate::service_gobject::ServiceGObject;
+use crate::vm_capabilities::Capability;
 use gtk::glib;
 
 #[derive(Debug, Clone, glib::Boxed)]
@@ -8,13 +14,73 @@ pub enum SettingsAction {
     RegionNLanguage {
         locale: String,
         timezone: String,
+        keyboard: KeyboardLayout,
+    },
+    SetClockFormat {
+        format: ClockFormat,
+    },
+    ConfigureOutput {
+        output: String,
+        mode: Mode,
+        scale: f64,
+    },
+    SetBrightness {
+        brightness: f64,
+    },
+    SetNightLight {
+        night_light: NightLightConfig,
+    },
+    AudioRefreshRequest,
+    SetDefaultAudioDevice {
+        kind: DeviceKind,
+        name: String,
+    },
+    SetAudioDeviceVolume {
+        kind: DeviceKind,
+        name: String,
+        volume: f64,
+    },
+    SetAudioDeviceMuted {
+        kind: DeviceKind,
+        name: String,
+        muted: bool,
+    },
+    SetVmVolume {
+        vm: String,
+        volume: f64,
+    },
+    SetVmMuted {
+        vm: String,
+        muted: bool,
+    },
+    NetworkRefreshRequest,
+    ScanWifi {
+        interface: String,
+    },
+    ConnectWifi {
+        interface: String,
+        ssid: String,
+        password: String,
+    },
+    DisconnectInterface {
+        interface: String,
+    },
+    Power {
+        action: PowerAction,
     },
     ShowErrorPopup {
         message: String,
     },
-    OpenWireGuard {
+    LaunchCapability {
         vm: ServiceGObject,
+        capability: Capability,
+    },
+    LaunchWith {
+        app: ServiceGObject,
+    },
+    MuteNotifications {
+        vm: String,
+        muted: bool,
     },
     CheckForUpdateRequest,
-    UpdateRequest,
 }
@@ -2,8 +2,8 @@
 use gio::{ListModel, ListStore};
 use glib::{Object, SignalHandlerId};
 use gtk::{
-    self, CustomFilter, DropDown, ListItem, SignalListItemFactory, SingleSelection, Widget, gio,
-    glib, prelude::*,
+    self, CustomFilter, DropDown, ListItem, MultiSelection, SignalListItemFactory, SingleSelection,
+    Widget, gio, glib, prelude::*,
 };
 use std::ops::Deref;
 
@@ -53,6 +53,18 @@ pub trait SingleSelectionExt: IsA<SingleSelection> {
 
 impl<L: IsA<SingleSelection>> SingleSelectionExt for L {}
 
+pub trait MultiSelectionExt: IsA<MultiSelection> {
+    fn selected_objs<T: IsA<Object>>(&self) -> Vec<T> {
+        let this = self.upcast_ref();
+        (0..this.n_items())
+            .filter(|&idx| this.is_selected(idx))
+            .filter_map(|idx| this.item(idx).and_downcast())
+            .collect()
+    }
+}
+
+impl<L: IsA<MultiSelection>> MultiSelectionExt for L {}
+
 pub trait DropDownExt: IsA<DropDown> {
     fn selected_obj<T: IsA<Object>>(&self) -> Option<T> {
         self.upcast_ref().selected_item().and_downcast()
@@ -0,0 +1,123 @@
+use futures::future::LocalBoxFuture;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error as ThisError;
+
+const ADMIN_UNSUPPORTED: &str = "The admin service does not provide system updates";
+
+/// Ghaf release, one TOML file per release in a manifest directory:
+///
+/// ```toml
+/// version = "25.12.1"
+/// changelog = "Fixes suspend on resume"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateManifest {
+    pub version: String,
+    #[serde(default)]
+    pub changelog: String,
+}
+
+#[derive(ThisError, Debug)]
+pub enum UpdateError {
+    #[error("{0}")]
+    Unsupported(&'static str),
+    #[error("the installed version is unknown")]
+    UnknownVersion,
+    #[error("{}: {source}", .path.display())]
+    Manifest {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("cannot read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Where releases come from, the admin service or a directory of manifests for testing.
+/// Only checking is supported, nothing can install a release yet.
+pub trait UpdateBackend {
+    /// Why no updates can be offered, `None` when they can
+    fn unsupported(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Newest release available, whatever the installed version
+    fn latest(&self) -> LocalBoxFuture<'_, Result<Option<UpdateManifest>, UpdateError>>;
+}
+
+/// Compares versions such as `25.06.1` by their numeric components, `25.06` < `25.06.1`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let components = |version: &str| -> Vec<u64> {
+        version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().unwrap_or(u64::MAX))
+            .collect()
+    };
+    components(a).cmp(&components(b))
+}
+
+/// Updates handled by the admin service on the host, which does not offer them yet
+#[derive(Debug)]
+pub struct AdminUpdateBackend;
+
+impl UpdateBackend for AdminUpdateBackend {
+    fn unsupported(&self) -> Option<&'static str> {
+        Some(ADMIN_UNSUPPORTED)
+    }
+
+    fn latest(&self) -> LocalBoxFuture<'_, Result<Option<UpdateManifest>, UpdateError>> {
+        Box::pin(async { Err(UpdateError::Unsupported(ADMIN_UNSUPPORTED)) })
+    }
+}
+
+/// Releases described by `*.toml` manifests in a local directory, for testing the check
+/// without an update server
+#[derive(Debug)]
+pub struct DirectoryUpdateBackend {
+    dir: PathBuf,
+}
+
+impl DirectoryUpdateBackend {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn read_manifests(&self) -> Result<Vec<UpdateManifest>, UpdateError> {
+        let entries = fs::read_dir(&self.dir).map_err(|source| UpdateError::Read {
+            path: self.dir.clone(),
+            source,
+        })?;
+        let mut manifests = Vec::new();
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            if path.extension().is_none_or(|extension| extension != "toml") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(|source| UpdateError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            let manifest = toml::from_str(&content)
+                .map_err(|source| UpdateError::Manifest { path, source })?;
+            manifests.push(manifest);
+        }
+        Ok(manifests)
+    }
+}
+
+impl UpdateBackend for DirectoryUpdateBackend {
+    fn latest(&self) -> LocalBoxFuture<'_, Result<Option<UpdateManifest>, UpdateError>> {
+        Box::pin(async move {
+            Ok(self
+                .read_manifests()?
+                .into_iter()
+                .max_by(|a, b| compare_versions(&a.version, &b.version)))
+        })
+    }
+}
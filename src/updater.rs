@@ -0,0 +1,126 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::prelude::*;
+use crate::update_backend::{self, UpdateBackend, UpdateError};
+
+/// Steps of the update check, installing releases is not supported yet
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "UpdateState")]
+pub enum UpdateState {
+    #[default]
+    Idle,
+    Checking,
+    UpToDate,
+    Available,
+    Failed,
+}
+
+mod imp {
+    use glib::Properties;
+    use gtk::glib;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use super::UpdateState;
+    use crate::update_backend::UpdateBackend;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::Updater)]
+    pub struct Updater {
+        #[property(get, set, builder(UpdateState::Idle))]
+        state: Cell<UpdateState>,
+        /// Installed Ghaf version, empty while unknown
+        #[property(get, set)]
+        current_version: RefCell<String>,
+        /// Newer release found by the last check, empty if none
+        #[property(get, set)]
+        available_version: RefCell<String>,
+        #[property(get, set)]
+        changelog: RefCell<String>,
+        /// Reason of the last failure
+        #[property(get, set)]
+        error: RefCell<String>,
+        /// Why the backend offers no updates, empty when it does
+        #[property(get, set)]
+        unsupported: RefCell<String>,
+
+        pub(super) backend: RefCell<Option<Rc<dyn UpdateBackend>>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Updater {
+        const NAME: &'static str = "Updater";
+        type Type = super::Updater;
+        type ParentType = glib::Object;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Updater {}
+}
+
+glib::wrapper! {
+    pub struct Updater(ObjectSubclass<imp::Updater>);
+}
+
+impl Default for Updater {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl Updater {
+    pub fn set_backend(&self, backend: impl UpdateBackend + 'static) {
+        self.set_unsupported(backend.unsupported().unwrap_or_default());
+        self.imp().backend.replace(Some(Rc::new(backend)));
+        self.set_available_version("");
+        self.set_changelog("");
+        self.set_state(UpdateState::Idle);
+    }
+
+    fn backend(&self) -> Option<Rc<dyn UpdateBackend>> {
+        self.imp().backend.borrow().clone()
+    }
+
+    /// Looks for a release newer than `current-version`
+    pub async fn check(&self) {
+        let Some(backend) = self.backend() else {
+            return;
+        };
+        if self.state() == UpdateState::Checking || backend.unsupported().is_some() {
+            return;
+        }
+        self.set_error("");
+        self.set_available_version("");
+        self.set_changelog("");
+        // Any release would look newer than an unknown version
+        if self.current_version().is_empty() {
+            return self.fail("Update check failed", &UpdateError::UnknownVersion);
+        }
+        self.set_state(UpdateState::Checking);
+        match backend.latest().await {
+            Ok(Some(manifest))
+                if update_backend::compare_versions(&manifest.version, &self.current_version())
+                    == Ordering::Greater =>
+            {
+                info!("Ghaf {} is available", manifest.version);
+                self.set_available_version(manifest.version.as_str());
+                self.set_changelog(manifest.changelog.as_str());
+                self.set_state(UpdateState::Available);
+            }
+            Ok(_) => self.set_state(UpdateState::UpToDate),
+            Err(e) => self.fail("Update check failed", &e),
+        }
+    }
+
+    fn fail(&self, what: &str, error: &UpdateError) {
+        warn!("{what}: {error}");
+        self.set_error(format!("{what}: {error}"));
+        self.set_state(UpdateState::Failed);
+    }
+}
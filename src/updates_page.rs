@@ -0,0 +1,161 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::updater::{UpdateState, Updater};
+
+mod imp {
+    use glib::subclass::Signal;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{Button, CompositeTemplate, Frame, Label, glib};
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use crate::updater::Updater;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/ae/tii/ghaf/controlpanelgui/ui/updates_page.ui")]
+    pub struct UpdatesPage {
+        #[template_child]
+        pub current_version_label: TemplateChild<Label>,
+        #[template_child]
+        pub available_version_label: TemplateChild<Label>,
+        #[template_child]
+        pub status_label: TemplateChild<Label>,
+        #[template_child]
+        pub changelog_frame: TemplateChild<Frame>,
+        #[template_child]
+        pub changelog_label: TemplateChild<Label>,
+        #[template_child]
+        pub check_button: TemplateChild<Button>,
+
+        pub(super) updater: RefCell<Option<Updater>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UpdatesPage {
+        const NAME: &'static str = "UpdatesPage";
+        type Type = super::UpdatesPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl UpdatesPage {
+        #[template_callback]
+        fn on_check_clicked(&self) {
+            self.obj().emit_by_name::<()>("check-for-update", &[]);
+        }
+    }
+
+    impl ObjectImpl for UpdatesPage {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("check-for-update").build()])
+        }
+    }
+    impl WidgetImpl for UpdatesPage {}
+    impl BoxImpl for UpdatesPage {}
+}
+
+glib::wrapper! {
+pub struct UpdatesPage(ObjectSubclass<imp::UpdatesPage>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for UpdatesPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UpdatesPage {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn set_updater(&self, updater: &Updater) {
+        let imp = self.imp();
+        updater
+            .bind_property("current-version", &*imp.current_version_label, "label")
+            .sync_create()
+            .transform_to(|_, version: String| {
+                Some(if version.is_empty() {
+                    String::from("unknown")
+                } else {
+                    version
+                })
+            })
+            .build();
+        updater
+            .bind_property("available-version", &*imp.available_version_label, "label")
+            .sync_create()
+            .transform_to(|_, version: String| {
+                Some(if version.is_empty() {
+                    String::from("-")
+                } else {
+                    version
+                })
+            })
+            .build();
+        updater
+            .bind_property("changelog", &*imp.changelog_label, "label")
+            .sync_create()
+            .build();
+        for property in ["state", "error", "changelog", "unsupported"] {
+            updater.connect_notify_local(
+                Some(property),
+                glib::clone!(
+                    #[weak(rename_to = page)]
+                    self,
+                    move |_, _| page.refresh()
+                ),
+            );
+        }
+        imp.updater.replace(Some(updater.clone()));
+        self.refresh();
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let Some(updater) = imp.updater.borrow().clone() else {
+            return;
+        };
+        let unsupported = updater.unsupported();
+        if !unsupported.is_empty() {
+            imp.status_label.set_label(&unsupported);
+            imp.status_label.remove_css_class("error");
+            imp.changelog_frame.set_visible(false);
+            imp.check_button.set_sensitive(false);
+            return;
+        }
+        let state = updater.state();
+        let version = updater.available_version();
+        imp.status_label.set_label(&match state {
+            UpdateState::Idle => String::from("Updates have not been checked yet"),
+            UpdateState::Checking => String::from("Checking for updates…"),
+            UpdateState::UpToDate => String::from("The system is up to date"),
+            UpdateState::Available => format!("Ghaf {version} is available"),
+            UpdateState::Failed => updater.error(),
+        });
+        if state == UpdateState::Failed {
+            imp.status_label.add_css_class("error");
+        } else {
+            imp.status_label.remove_css_class("error");
+        }
+        imp.changelog_frame
+            .set_visible(!updater.changelog().is_empty());
+        imp.check_button
+            .set_sensitive(state != UpdateState::Checking);
+    }
+}
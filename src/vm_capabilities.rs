@@ -0,0 +1,162 @@
+use gtk::glib;
+use gtk::subclass::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+
+/// Tool app a VM provides, e.g. the WireGuard GUI or a file manager
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Capability {
+    pub label: String,
+    /// Application name passed to the admin service
+    pub app: String,
+    /// Icon theme name
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl Capability {
+    fn wireguard() -> Self {
+        Self {
+            label: "Wireguard GUI".into(),
+            app: "wireguard-gui".into(),
+            icon: None,
+            args: Vec::new(),
+        }
+    }
+}
+
+/// Capabilities file layout:
+///
+/// ```toml
+/// [[vm.business-vm]]
+/// label = "Wireguard GUI"
+/// app = "wireguard-gui"
+/// icon = "network-vpn-symbolic"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct CapabilitiesFile {
+    #[serde(default, rename = "vm")]
+    vms: BTreeMap<String, Vec<Capability>>,
+}
+
+/// The old `wireguard-gui-vms.txt` format: one VM name per line, each of them
+/// providing the WireGuard GUI
+fn is_legacy_list(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .all(|line| !line.contains(['=', '[', ' ']))
+}
+
+fn parse(content: &str) -> Result<BTreeMap<String, Vec<Capability>>, toml::de::Error> {
+    match toml::from_str::<CapabilitiesFile>(content) {
+        Ok(file) => Ok(file.vms),
+        Err(_) if is_legacy_list(content) => Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|vm| (vm.to_owned(), vec![Capability::wireguard()]))
+            .collect()),
+        Err(e) => Err(e),
+    }
+}
+
+mod imp {
+    use gtk::glib;
+    use gtk::subclass::prelude::*;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::Capability;
+    use crate::file_watcher::FileWatcher;
+
+    #[derive(Debug, Default)]
+    pub struct VmCapabilities {
+        pub(super) path: RefCell<PathBuf>,
+        pub(super) vms: RefCell<BTreeMap<String, Vec<Capability>>>,
+        pub(super) watcher: FileWatcher,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VmCapabilities {
+        const NAME: &'static str = "VmCapabilities";
+        type Type = super::VmCapabilities;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for VmCapabilities {}
+}
+
+glib::wrapper! {
+    pub struct VmCapabilities(ObjectSubclass<imp::VmCapabilities>);
+}
+
+impl Default for VmCapabilities {
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
+
+impl VmCapabilities {
+    /// Loads the capabilities file and reloads it whenever it changes on disk
+    pub fn watch(&self, path: &Path) {
+        *self.imp().path.borrow_mut() = path.to_owned();
+        self.imp().watcher.watch(
+            &[path],
+            glib::clone!(
+                #[weak(rename_to = caps)]
+                self,
+                #[upgrade_or_default]
+                move || caps.reload()
+            ),
+        );
+        self.imp().watcher.reload();
+    }
+
+    /// True when the capabilities differ from the ones read before
+    fn reload(&self) -> bool {
+        let path: PathBuf = self.imp().path.borrow().clone();
+        let vms = match std::fs::read_to_string(&path) {
+            Ok(content) => parse(&content).unwrap_or_else(|e| {
+                error!(
+                    "Failed to parse capabilities file {path}: {e}",
+                    path = path.display()
+                );
+                BTreeMap::new()
+            }),
+            Err(e) => {
+                error!(
+                    "Failed to read the file '{path}': {e}",
+                    path = path.display()
+                );
+                BTreeMap::new()
+            }
+        };
+        info!("VM capabilities have been read: {vms:?}");
+        if *self.imp().vms.borrow() == vms {
+            return false;
+        }
+        *self.imp().vms.borrow_mut() = vms;
+        true
+    }
+
+    pub fn for_vm(&self, vm: &str) -> Vec<Capability> {
+        self.imp().vms.borrow().get(vm).cloned().unwrap_or_default()
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.imp().watcher.connect_changed(glib::clone!(
+            #[weak(rename_to = caps)]
+            self,
+            move |_| f(&caps)
+        ))
+    }
+}
@@ -1,22 +1,59 @@
 use adw::subclass::prelude::*;
-use gio::ListModel;
 use gtk::prelude::*;
 use gtk::{gio, glib};
 
 use crate::application::ControlPanelGuiApplication;
 pub use crate::application::StatsResponse;
+use crate::audio_backend::AudioState;
+use crate::config::NightLightConfig;
+use crate::display_backend::Output;
+use crate::locale_provider::{KeyboardLayout, KeyboardLayouts, LanguageRegionEntry, RegionChange};
+use crate::network_backend::NetworkState;
 use crate::prelude::*;
+use crate::service_gobject::ServiceGObject;
+use crate::vm_capabilities::Capability;
+
+/// Pages which can be opened from outside the window
+#[derive(Debug, Clone, Copy, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Page {
+    Services,
+    Launcher,
+    Activity,
+    Settings,
+    #[strum(serialize = "locale", serialize = "settings/locale")]
+    Locale,
+    #[strum(serialize = "datetime", serialize = "settings/datetime")]
+    DateTime,
+    #[strum(serialize = "display", serialize = "settings/display")]
+    Display,
+    #[strum(serialize = "audio", serialize = "settings/audio")]
+    Audio,
+    #[strum(serialize = "network", serialize = "settings/network")]
+    Network,
+    #[strum(serialize = "power", serialize = "settings/power")]
+    Power,
+    #[strum(serialize = "updates", serialize = "settings/updates")]
+    Updates,
+    #[strum(serialize = "about", serialize = "settings/about")]
+    About,
+}
 
 mod imp {
     use adw::subclass::prelude::*;
     use gio::ListModel;
+    use givc_common::query::TrustLevel;
+    use glib::Binding;
     use gtk::prelude::*;
     use gtk::{
-        CompositeTemplate, Image, ListView, MenuButton, SingleSelection, Stack, ToggleButton, gio,
-        glib,
+        Box, CompositeTemplate, CustomFilter, DropDown, FilterListModel, Image, Label, ListView,
+        MenuButton, MultiSelection, Revealer, SingleSelection, Stack, ToggleButton, gio, glib,
     };
+    use std::cell::RefCell;
 
+    use crate::activity_page::ActivityPage;
     use crate::control_action::ControlAction;
+    use crate::launcher_page::LauncherPage;
     use crate::prelude::*;
     use crate::service_gobject::ServiceGObject;
     use crate::service_row::ServiceRow;
@@ -33,10 +70,16 @@ mod imp {
         #[template_child]
         pub header_menu_button: TemplateChild<MenuButton>,
         #[template_child]
+        pub profile_dropdown: TemplateChild<DropDown>,
+        #[template_child]
         pub vm_view_button: TemplateChild<ToggleButton>,
         #[template_child]
         pub settings_view_button: TemplateChild<ToggleButton>,
         #[template_child]
+        pub launcher_view_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub activity_view_button: TemplateChild<ToggleButton>,
+        #[template_child]
         pub ghaf_logo: TemplateChild<Image>,
 
         #[template_child]
@@ -45,10 +88,43 @@ mod imp {
         #[template_child]
         pub services_list_view: TemplateChild<ListView>,
         #[template_child]
+        pub secure_filter_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub warning_filter_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub alert_filter_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub secure_count_label: TemplateChild<Label>,
+        #[template_child]
+        pub warning_count_label: TemplateChild<Label>,
+        #[template_child]
+        pub alert_count_label: TemplateChild<Label>,
+        #[template_child]
         pub service_settings_box: TemplateChild<ServiceSettings>,
+        #[template_child]
+        pub select_mode_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub bulk_action_revealer: TemplateChild<Revealer>,
+        #[template_child]
+        pub bulk_selection_label: TemplateChild<Label>,
+        #[template_child]
+        pub bulk_action_box: TemplateChild<Box>,
 
         #[template_child]
         pub settings_box: TemplateChild<Settings>,
+
+        #[template_child]
+        pub launcher_page: TemplateChild<LauncherPage>,
+
+        #[template_child]
+        pub activity_page: TemplateChild<ActivityPage>,
+
+        services_model: RefCell<Option<ListModel>>,
+        /// All units, `services_model` only holds those passing the trust filter
+        all_services_model: RefCell<Option<ListModel>>,
+        trust_filter: RefCell<Option<CustomFilter>>,
+        multi_selection: RefCell<Option<MultiSelection>>,
+        visibility_binding: RefCell<Option<Binding>>,
     }
 
     #[glib::object_subclass]
@@ -100,6 +176,33 @@ mod imp {
             }
         }
 
+        #[template_callback]
+        fn switch_to_launcher_view(&self) {
+            if self.stack.visible_child_name() != Some("launcher_view".into()) {
+                self.stack.set_visible_child_name("launcher_view");
+            }
+            self.refresh_launcher();
+        }
+
+        #[template_callback]
+        fn switch_to_activity_view(&self) {
+            if self.stack.visible_child_name() != Some("activity_view".into()) {
+                self.stack.set_visible_child_name("activity_view");
+            }
+        }
+
+        #[template_callback]
+        fn on_launch_app(&self, name: String, vm: String, args: Vec<String>) {
+            self.obj().get_app_ref().launch_app(name, vm, args);
+        }
+
+        pub fn refresh_launcher(&self) {
+            if self.stack.visible_child_name() == Some("launcher_view".into()) {
+                let apps = self.obj().get_app_ref().available_apps();
+                self.launcher_page.set_apps(&apps);
+            }
+        }
+
         #[template_callback]
         fn on_control_action(&self, action: ControlAction, object: ServiceGObject) {
             let app = self.obj().get_app_ref();
@@ -112,16 +215,140 @@ mod imp {
             app.perform_setting_action(action);
         }
 
+        #[template_callback]
+        fn on_select_mode_toggled(&self) {
+            let multi = self.select_mode_button.is_active();
+            self.bulk_action_revealer.set_reveal_child(multi);
+            if multi {
+                self.set_multi_selection_model();
+            } else {
+                self.set_single_selection_model();
+            }
+        }
+
+        fn emit_bulk_action(&self, action: ControlAction) {
+            let Some(selection) = self.multi_selection.borrow().clone() else {
+                return;
+            };
+            let objects = selection.selected_objs::<ServiceGObject>();
+            if objects.is_empty() {
+                return;
+            }
+            self.obj().get_app_ref().control_services(action, objects);
+            selection.unselect_all();
+        }
+
+        #[template_callback]
+        fn on_bulk_start_clicked(&self) {
+            self.emit_bulk_action(ControlAction::Start);
+        }
+
+        #[template_callback]
+        fn on_bulk_pause_clicked(&self) {
+            self.emit_bulk_action(ControlAction::Pause);
+        }
+
+        #[template_callback]
+        fn on_bulk_resume_clicked(&self) {
+            self.emit_bulk_action(ControlAction::Resume);
+        }
+
+        #[template_callback]
+        fn on_bulk_shutdown_clicked(&self) {
+            self.emit_bulk_action(ControlAction::Shutdown);
+        }
+
+        #[template_callback]
+        fn on_trust_filter_toggled(&self) {
+            if let Some(filter) = self.trust_filter.borrow().as_ref() {
+                filter.changed(gtk::FilterChange::Different);
+            }
+        }
+
         pub fn setup_service_rows(&self, model: &ListModel) {
+            let filter = CustomFilter::typed(glib::clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                #[upgrade_or]
+                true,
+                move |obj: &ServiceGObject| window.imp().passes_trust_filter(obj)
+            ));
+            let filtered = FilterListModel::new(Some(model.clone()), Some(filter.clone()));
+            model.connect_items_changed(glib::clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                move |_, _, _, _| window.imp().update_security_summary()
+            ));
+            *self.trust_filter.borrow_mut() = Some(filter);
+            *self.all_services_model.borrow_mut() = Some(model.clone());
+            *self.services_model.borrow_mut() = Some(filtered.upcast());
+            self.update_security_summary();
+            self.set_single_selection_model();
+        }
+
+        fn trust_filter_buttons(&self) -> [&ToggleButton; 3] {
+            [
+                &self.secure_filter_button,
+                &self.warning_filter_button,
+                &self.alert_filter_button,
+            ]
+        }
+
+        /// Units pass when no trust level is picked, or when theirs is
+        fn passes_trust_filter(&self, obj: &ServiceGObject) -> bool {
+            let button = match obj.trust_level() {
+                TrustLevel::Secure => &self.secure_filter_button,
+                TrustLevel::Warning => &self.warning_filter_button,
+                TrustLevel::NotSecure => &self.alert_filter_button,
+            };
+            button.is_active()
+                || !self
+                    .trust_filter_buttons()
+                    .iter()
+                    .any(|button| button.is_active())
+        }
+
+        pub fn clear_trust_filter(&self) {
+            for button in self.trust_filter_buttons() {
+                button.set_active(false);
+            }
+        }
+
+        /// Recounts the units per trust level and refilters after a trust change
+        pub fn on_unit_trust_changed(&self) {
+            self.update_security_summary();
+            self.on_trust_filter_toggled();
+        }
+
+        fn update_security_summary(&self) {
+            let Some(model) = self.all_services_model.borrow().clone() else {
+                return;
+            };
+            let (mut secure, mut warning, mut alert) = (0, 0, 0);
+            for obj in model.wrap::<ServiceGObject>().iter() {
+                match obj.trust_level() {
+                    TrustLevel::Secure => secure += 1,
+                    TrustLevel::Warning => warning += 1,
+                    TrustLevel::NotSecure => alert += 1,
+                }
+            }
+            self.secure_count_label.set_label(&secure.to_string());
+            self.warning_count_label.set_label(&warning.to_string());
+            self.alert_count_label.set_label(&alert.to_string());
+        }
+
+        fn set_single_selection_model(&self) {
+            let Some(model) = self.services_model.borrow().clone() else {
+                return;
+            };
+            self.multi_selection.borrow_mut().take();
             let selection_model =
                 SingleSelection::new(Some(model.clone())).wrap::<ServiceGObject>();
             selection_model.connect_selection_changed(glib::clone!(
-                #[strong(rename_to = window)]
+                #[weak(rename_to = window)]
                 self.obj(),
-                #[strong]
-                selection_model,
-                move |_, _, _| {
-                    if let Some(obj) = selection_model.selected_obj() {
+                move |selection_model, _, _| {
+                    if let Some(obj) = selection_model.selected_obj::<ServiceGObject>() {
                         let title = obj.name();
                         let subtitle = obj.details();
                         debug!("Property {title}, {subtitle}");
@@ -132,13 +359,13 @@ mod imp {
                 }
             ));
             selection_model.connect_items_changed(glib::clone!(
-                #[strong(rename_to = window)]
+                #[weak(rename_to = window)]
                 self.obj(),
                 move |selection_model, position, removed, added| {
                     debug!(
                         "Items changed at position {position}, removed: {removed}, added: {added}"
                     );
-                    if let Some(obj) = selection_model.selected_obj() {
+                    if let Some(obj) = selection_model.selected_obj::<ServiceGObject>() {
                         window.imp().set_vm_details(&obj);
                     } else {
                         debug!("No item selected");
@@ -151,14 +378,57 @@ mod imp {
             Self::set_default_selection(&selection_model, model.n_items());
         }
 
+        fn set_multi_selection_model(&self) {
+            let Some(model) = self.services_model.borrow().clone() else {
+                return;
+            };
+            let selection_model = MultiSelection::new(Some(model));
+            selection_model.connect_selection_changed(glib::clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                move |selection_model, _, _| {
+                    window.imp().update_bulk_selection(selection_model);
+                }
+            ));
+            selection_model.connect_items_changed(glib::clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                move |selection_model, _, _, _| {
+                    window.imp().update_bulk_selection(selection_model);
+                }
+            ));
+
+            self.services_list_view.set_model(Some(&selection_model));
+            self.bind_service_settings_box_visibility();
+            self.update_bulk_selection(&selection_model);
+            *self.multi_selection.borrow_mut() = Some(selection_model);
+        }
+
+        fn update_bulk_selection(&self, selection_model: &MultiSelection) {
+            let selected = selection_model.selected_objs::<ServiceGObject>();
+            self.bulk_selection_label.set_label(&match selected.len() {
+                0 => String::from("No units selected"),
+                1 => String::from("1 unit selected"),
+                n => format!("{n} units selected"),
+            });
+            self.bulk_action_box.set_sensitive(!selected.is_empty());
+            if let [obj] = selected.as_slice() {
+                self.set_vm_details(obj);
+            }
+        }
+
         fn bind_service_settings_box_visibility(&self) {
             let service_settings_box = self.service_settings_box.upcast_ref::<gtk::Widget>();
+            if let Some(binding) = self.visibility_binding.borrow_mut().take() {
+                binding.unbind();
+            }
             if let Some(model) = self.services_list_view.model() {
-                model
+                let binding = model
                     .bind_property("n_items", service_settings_box, "visible")
                     .sync_create()
                     .transform_to(move |_, count: u32| Some(count != 0))
                     .build();
+                *self.visibility_binding.borrow_mut() = Some(binding);
             }
         }
 
@@ -241,11 +511,59 @@ impl ControlPanelGuiWindow {
         //get application reference
 
         self.imp().setup_service_rows(&app.get_model());
+        app.get_model().connect_items_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, _, _, _| window.imp().refresh_launcher()
+        ));
+        app.connect_unit_status_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.imp().on_unit_trust_changed()
+        ));
+        app.vm_capabilities().connect_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.imp().service_settings_box.refresh_capabilities()
+        ));
+        app.app_catalog().connect_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.imp().refresh_launcher()
+        ));
+        self.imp().activity_page.set_log(&app.activity_log());
+        self.imp().settings_box.set_updater(&app.updater());
+        self.imp().settings_box.set_clock_format(app.clock_format());
+        self.imp().settings_box.set_night_light(&app.night_light());
+        self.setup_profile_dropdown(&app);
         self.imp().setup_factory();
         //vm view by default
         self.imp().vm_view_button.set_active(true);
     }
 
+    /// The switcher is only shown when further profiles are configured
+    fn setup_profile_dropdown(&self, app: &ControlPanelGuiApplication) {
+        let names = app.profile_names();
+        let active = app.active_profile().name;
+        let dropdown = &self.imp().profile_dropdown;
+        let model: gtk::StringList = names.iter().cloned().collect();
+        dropdown.set_model(Some(&model));
+        if let Some(index) = names.iter().position(|name| *name == active) {
+            dropdown.set_selected(u32::try_from(index).unwrap_or(0));
+        }
+        dropdown.set_visible(names.len() > 1);
+
+        dropdown.connect_selected_item_notify(glib::clone!(
+            #[weak]
+            app,
+            move |dropdown| {
+                if let Some(name) = dropdown.selected_item().and_downcast::<gtk::StringObject>() {
+                    app.switch_profile(&name.string());
+                }
+            }
+        ));
+    }
+
     #[inline]
     fn get_app_ref(&self) -> ControlPanelGuiApplication {
         let binding = self.application().expect("Failed to get application");
@@ -278,11 +596,124 @@ impl ControlPanelGuiWindow {
     }
 
     //pub API
-    pub fn set_locale_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
-        self.imp().settings_box.set_locale_model(model, selected);
+    pub fn show_page(&self, page: Page) {
+        let imp = self.imp();
+        match page {
+            Page::Services => imp.vm_view_button.set_active(true),
+            Page::Launcher => imp.launcher_view_button.set_active(true),
+            Page::Activity => imp.activity_view_button.set_active(true),
+            Page::Settings => imp.settings_view_button.set_active(true),
+            Page::Locale => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("locale");
+            }
+            Page::DateTime => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("datetime");
+            }
+            Page::Display => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("display");
+            }
+            Page::Audio => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("audio");
+            }
+            Page::Network => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("network");
+            }
+            Page::Power => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("power");
+            }
+            Page::Updates => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("updates");
+            }
+            Page::About => {
+                imp.settings_view_button.set_active(true);
+                imp.settings_box.show_page("about");
+            }
+        }
+    }
+
+    /// Shows the services page with `unit` selected, VMs may be given by their VM name
+    pub fn select_unit(&self, unit: &str) -> bool {
+        let imp = self.imp();
+        imp.select_mode_button.set_active(false);
+        imp.clear_trust_filter();
+        self.show_page(Page::Services);
+        let Some(model) = imp.services_list_view.model() else {
+            return false;
+        };
+        let Some(position) = model
+            .wrap::<ServiceGObject>()
+            .iter()
+            .position(|obj| obj.name() == unit || (obj.is_vm() && obj.vm_name() == unit))
+            .and_then(|position| u32::try_from(position).ok())
+        else {
+            return false;
+        };
+        imp.services_list_view.scroll_to(
+            position,
+            gtk::ListScrollFlags::SELECT | gtk::ListScrollFlags::FOCUS,
+            None,
+        );
+        true
+    }
+
+    pub fn vm_capabilities(&self, vm: &str) -> Vec<Capability> {
+        self.get_app_ref().vm_capabilities().for_vm(vm)
+    }
+
+    pub fn is_vm_muted(&self, vm: &str) -> bool {
+        self.get_app_ref().is_vm_muted(vm)
+    }
+
+    pub fn set_locales(&self, locales: Vec<LanguageRegionEntry>, current: Option<&str>) {
+        self.imp().settings_box.set_locales(locales, current);
+    }
+
+    pub fn set_timezones(&self, timezones: Vec<LanguageRegionEntry>, current: Option<&str>) {
+        self.imp().settings_box.set_timezones(timezones, current);
+    }
+
+    pub fn set_keyboard_layouts(
+        &self,
+        keyboard: KeyboardLayouts,
+        current: Option<&KeyboardLayout>,
+    ) {
+        self.imp()
+            .settings_box
+            .set_keyboard_layouts(keyboard, current);
+    }
+
+    pub fn show_region_change(&self, change: &RegionChange) {
+        self.imp().settings_box.show_region_change(change);
+    }
+
+    pub fn set_outputs(&self, outputs: Vec<Output>) {
+        self.imp().settings_box.set_outputs(outputs);
+    }
+
+    pub fn set_brightness(&self, brightness: Option<f64>) {
+        self.imp().settings_box.set_brightness(brightness);
+    }
+
+    pub fn set_night_light(&self, config: &NightLightConfig) {
+        self.imp().settings_box.set_night_light(config);
+    }
+
+    pub fn set_audio_state(&self, state: Result<AudioState, String>) {
+        self.imp().settings_box.set_audio_state(state);
+    }
+
+    pub fn set_network_state(&self, state: Result<NetworkState, String>) {
+        self.imp().settings_box.set_network_state(state);
     }
 
-    pub fn set_timezone_model(&self, model: impl IsA<ListModel>, selected: Option<usize>) {
-        self.imp().settings_box.set_timezone_model(model, selected);
+    pub fn set_wifi_scanning(&self, scanning: bool) {
+        self.imp().settings_box.set_wifi_scanning(scanning);
     }
 }
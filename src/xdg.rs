@@ -0,0 +1,17 @@
+use gtk::glib;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "ctrl-panel";
+
+/// Per-user configuration directory, `$XDG_CONFIG_HOME/ctrl-panel`
+pub fn config_dir() -> PathBuf {
+    glib::user_config_dir().join(APP_DIR)
+}
+
+/// Per-user state directory, `$XDG_STATE_HOME/ctrl-panel`
+pub fn state_dir() -> PathBuf {
+    std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| glib::home_dir().join(".local/state"), PathBuf::from)
+        .join(APP_DIR)
+}